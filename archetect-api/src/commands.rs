@@ -4,9 +4,11 @@ use serde::{Deserialize, Serialize};
 
 pub use crate::commands::bool_prompt_info::BoolPromptInfo;
 pub use crate::commands::editor_prompt_info::EditorPromptInfo;
+pub use crate::commands::file_event_info::FileEventInfo;
 pub use crate::commands::int_prompt_info::IntPromptInfo;
 pub use crate::commands::list_prompt_info::ListPromptInfo;
 pub use crate::commands::multiselect_prompt_info::MultiSelectPromptInfo;
+pub use crate::commands::overwrite_prompt_info::{OverwriteDecision, OverwritePromptInfo};
 pub use crate::commands::prompt_info::{
     PromptInfo, PromptInfoItemsRestrictions, PromptInfoLengthRestrictions, PromptInfoPageable,
 };
@@ -15,9 +17,11 @@ pub use crate::commands::text_prompt_info::TextPromptInfo;
 
 mod bool_prompt_info;
 mod editor_prompt_info;
+mod file_event_info;
 mod int_prompt_info;
 mod list_prompt_info;
 mod multiselect_prompt_info;
+mod overwrite_prompt_info;
 mod prompt_info;
 mod select_prompt_info;
 mod text_prompt_info;
@@ -38,6 +42,8 @@ pub enum CommandRequest {
     PromptForMultiSelect(MultiSelectPromptInfo),
     /// Prompt to gather text from an editor
     PromptForEditor(EditorPromptInfo),
+    /// Prompt whether to overwrite an existing file with newly rendered contents
+    PromptForOverwrite(OverwritePromptInfo),
     /// Log a String at Trace Level
     LogTrace(String),
     /// Log a String at Debug Level
@@ -52,6 +58,31 @@ pub enum CommandRequest {
     Print(String),
     /// Print a String that show not be captured as output, such as on STDERR
     Display(String),
+    /// A directory was created while rendering
+    DirectoryCreated(String),
+    /// A file was rendered from a template, or a link recreated
+    FileRendered(FileEventInfo),
+    /// A file was copied without being rendered
+    FileCopied(FileEventInfo),
+    /// An existing file was preserved according to the overwrite policy
+    FilePreserved(FileEventInfo),
+    /// An existing file was preserved because overwriting it was declined when prompted
+    OverwriteSkipped(FileEventInfo),
+}
+
+impl CommandRequest {
+    /// Whether this request reports the progress of a render, rather than prompting or logging.  These are only
+    /// sent to drivers that opt into them through [IoDriver::render_events](crate::IoDriver::render_events).
+    pub fn is_render_event(&self) -> bool {
+        matches!(
+            self,
+            CommandRequest::DirectoryCreated(_)
+                | CommandRequest::FileRendered(_)
+                | CommandRequest::FileCopied(_)
+                | CommandRequest::FilePreserved(_)
+                | CommandRequest::OverwriteSkipped(_)
+        )
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -60,6 +91,7 @@ pub enum CommandResponse {
     Integer(i64),
     Boolean(bool),
     Array(Vec<String>),
+    Overwrite(OverwriteDecision),
     None,
     Error(String),
     Abort,
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// A file written, or deliberately left untouched, while rendering a directory.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FileEventInfo {
+    destination: String,
+    source: String,
+}
+
+impl FileEventInfo {
+    pub fn new<D: Into<String>, S: Into<String>>(destination: D, source: S) -> Self {
+        FileEventInfo {
+            destination: destination.into(),
+            source: source.into(),
+        }
+    }
+
+    pub fn destination(&self) -> &str {
+        self.destination.as_str()
+    }
+
+    pub fn source(&self) -> &str {
+        self.source.as_str()
+    }
+}
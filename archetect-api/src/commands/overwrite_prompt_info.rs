@@ -0,0 +1,71 @@
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+/// Asks whether an existing file should be overwritten with freshly rendered contents.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OverwritePromptInfo {
+    destination: String,
+    existing: String,
+    proposed: String,
+}
+
+impl OverwritePromptInfo {
+    pub fn new<D: Into<String>, E: Into<String>, P: Into<String>>(
+        destination: D,
+        existing: E,
+        proposed: P,
+    ) -> Self {
+        OverwritePromptInfo {
+            destination: destination.into(),
+            existing: existing.into(),
+            proposed: proposed.into(),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        format!("Overwrite '{}'?", self.destination)
+    }
+
+    pub fn destination(&self) -> &str {
+        self.destination.as_str()
+    }
+
+    pub fn existing(&self) -> &str {
+        self.existing.as_str()
+    }
+
+    pub fn proposed(&self) -> &str {
+        self.proposed.as_str()
+    }
+}
+
+/// The answer to an [OverwritePromptInfo].  `All` and `None` apply to every remaining file of the render.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum OverwriteDecision {
+    Yes,
+    No,
+    All,
+    None,
+}
+
+impl OverwriteDecision {
+    pub fn overwrite(&self) -> bool {
+        matches!(self, OverwriteDecision::Yes | OverwriteDecision::All)
+    }
+
+    pub fn applies_to_all(&self) -> bool {
+        matches!(self, OverwriteDecision::All | OverwriteDecision::None)
+    }
+}
+
+impl Display for OverwriteDecision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OverwriteDecision::Yes => write!(f, "yes"),
+            OverwriteDecision::No => write!(f, "no"),
+            OverwriteDecision::All => write!(f, "all"),
+            OverwriteDecision::None => write!(f, "none"),
+        }
+    }
+}
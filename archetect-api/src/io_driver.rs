@@ -9,6 +9,12 @@ pub trait IoDriver: Debug + Send + Sync + 'static {
 
     fn responses(&self) -> Arc<Mutex<Receiver<CommandResponse>>>;
 
+    /// Whether this driver should be sent render events, such as [CommandRequest::FileRendered], as each directory
+    /// and file of a render is dealt with.  Drivers only handling prompts and logs can leave these disabled.
+    fn render_events(&self) -> bool {
+        false
+    }
+
     fn receive(&self) -> CommandResponse {
         self.responses()
             .lock()
@@ -30,6 +36,7 @@ pub fn api_driver_and_handle() -> (ApiIoDriver, ApiIoHandle) {
     let driver = ApiIoDriver {
         requests_tx,
         responses_rx: Arc::new(Mutex::new(responses_rx)),
+        render_events: false,
     };
     let handle = ApiIoHandle {
         responses_tx,
@@ -42,6 +49,15 @@ pub fn api_driver_and_handle() -> (ApiIoDriver, ApiIoHandle) {
 pub struct ApiIoDriver {
     requests_tx: SyncSender<CommandRequest>,
     responses_rx: Arc<Mutex<Receiver<CommandResponse>>>,
+    render_events: bool,
+}
+
+impl ApiIoDriver {
+    /// Enables sending render events to the [ApiIoHandle], which must then be drained as a render progresses.
+    pub fn with_render_events(mut self, value: bool) -> Self {
+        self.render_events = value;
+        self
+    }
 }
 
 impl IoDriver for ApiIoDriver {
@@ -52,6 +68,10 @@ impl IoDriver for ApiIoDriver {
     fn responses(&self) -> Arc<Mutex<Receiver<CommandResponse>>> {
         self.responses_rx.clone()
     }
+
+    fn render_events(&self) -> bool {
+        self.render_events
+    }
 }
 
 pub struct ApiIoHandle {
@@ -5,6 +5,7 @@ use clap::{command, value_parser, Arg, ArgAction, ArgMatches, Command};
 use clap_complete::{generate, Shell};
 use log::Level;
 
+use archetect_core::archetype::output_sink::ArchiveFormat;
 use archetect_core::errors::ArchetectError;
 
 use crate::cli;
@@ -51,6 +52,28 @@ pub fn command() -> Command {
                 )
                 .args(render_args(true)),
         )
+        .subcommand(
+            Command::new("replay")
+                .about("Re-render a project from the record written when it was rendered")
+                .long_about(
+                    "Re-render a project from the record written to .archetect/render.yaml when it was rendered, \
+                    using the recorded source, switches, and answers without prompting",
+                )
+                .arg(
+                    Arg::new("destination")
+                        .help("The directory of the previously rendered project")
+                        .default_value(".")
+                        .action(ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("gitref")
+                        .help("Render from this git branch, tag, or commit instead of the recorded commit")
+                        .long("ref")
+                        .action(ArgAction::Set)
+                        .value_name("gitref"),
+                )
+                .args(render_args(true)),
+        )
         .subcommand(
             Command::new("config")
                 .arg_required_else_help(true)
@@ -218,6 +241,70 @@ fn render_args(global: bool) -> Vec<Arg> {
             .global(global),
     );
 
+    args.push(
+        Arg::new("dry-run")
+            .help("Run the Archetype without writing any files or executing commands, printing a plan of what would be rendered")
+            .long("dry-run")
+            .action(ArgAction::SetTrue)
+            .global(global),
+    );
+    args.push(
+        Arg::new("no-transaction")
+            .help("Write rendered files directly into the destination, rather than staging them in a temporary directory and only moving them into the destination if the render succeeds")
+            .long("no-transaction")
+            .action(ArgAction::SetTrue)
+            .global(global),
+    );
+    args.push(
+        Arg::new("no-record")
+            .help("Skip writing a record of the render to .archetect/render.yaml in the destination")
+            .long("no-record")
+            .action(ArgAction::SetTrue)
+            .global(global),
+    );
+    args.push(
+        Arg::new("git-init")
+            .help("Initialize a git repository in the destination and commit the rendered files, as if the Archetype set git.init")
+            .long("git-init")
+            .action(ArgAction::SetTrue)
+            .global(global),
+    );
+    args.push(
+        Arg::new("plan-format")
+            .help("The format used to print the plan produced by --dry-run")
+            .long("plan-format")
+            .value_parser(["tree", "json"])
+            .default_value("tree")
+            .action(ArgAction::Set)
+            .value_name("format")
+            .global(global),
+    );
+    args.push(
+        Arg::new("report")
+            .help("Write a report of every rendered file, with the answers and components used, as JSON, or as YAML if the path ends in .yaml or .yml")
+            .long("report")
+            .action(ArgAction::Set)
+            .value_name("path")
+            .global(global),
+    );
+    args.push(
+        Arg::new("output-archive")
+            .help("Write rendered files into an archive rather than the destination, as a tar, tar.gz, or zip according to the path's extension")
+            .long("output-archive")
+            .action(ArgAction::Set)
+            .value_parser(parse_archive_path)
+            .value_name("path")
+            .conflicts_with("output-zip")
+            .global(global),
+    );
+    args.push(
+        Arg::new("output-zip")
+            .help("Write rendered files into a zip archive rather than the destination")
+            .long("output-zip")
+            .action(ArgAction::Set)
+            .value_name("path")
+            .global(global),
+    );
     args.push(
         Arg::new("offline")
             .help("Only use directories and already-cached remote git URLs")
@@ -270,6 +357,13 @@ fn render_args(global: bool) -> Vec<Arg> {
     args
 }
 
+fn parse_archive_path(path: &str) -> Result<String, String> {
+    match ArchiveFormat::from_path(path) {
+        Some(_) => Ok(path.to_owned()),
+        None => Err("expected a path ending in .tar, .tar.gz, .tgz, or .zip".to_owned()),
+    }
+}
+
 pub fn configure(matches: &ArgMatches) {
     loggerv::Logger::new()
         .output(&Level::Error, loggerv::Output::Stderr)
@@ -62,6 +62,13 @@ pub fn load_user_config<L: SystemLayout>(layout: &L, args: &ArgMatches) -> Resul
     );
     let config = config.add_source(ClapSource::new(args.clone(), mappings));
 
+    // Replays must reproduce a render from its record alone, without waiting on input
+    let config = if let Some(("replay", _)) = args.subcommand() {
+        config.set_override("headless", true)?
+    } else {
+        config
+    };
+
     let config = config.build()?;
     config.try_deserialize()
 }
@@ -10,7 +10,10 @@ use archetect_core::{self};
 use archetect_core::actions::ArchetectAction;
 use archetect_core::Archetect;
 use archetect_core::archetype::archetype::Archetype;
+use archetect_core::archetype::output_sink::{ArchiveFormat, ArchiveSink};
 use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::archetype::render_record::RenderRecord;
+use archetect_core::archetype::render_report::RenderReport;
 use archetect_core::catalog::{Catalog, CatalogManifest};
 use archetect_core::configuration::Configuration;
 use archetect_core::errors::{ArchetectError, ArchetypeError, CatalogError, SourceError};
@@ -62,28 +65,7 @@ fn execute<D: IoDriver, L: SystemLayout>(matches: ArgMatches, driver: D, layout:
     for (identifier, value) in configuration.answers() {
         answers.insert(identifier.clone(), value.clone());
     }
-
-    // Load answers from answer files
-    if let Some(answer_files) = matches.get_many::<String>("answer-file") {
-        for answer_file in answer_files {
-            let results = answers::read_answers(answer_file)?;
-            answers.extend(results);
-        }
-    }
-
-    // Load answers from individual answer arguments
-    if let Some(answer_matches) = matches.get_many::<String>("answer") {
-        for answer_match in answer_matches {
-            let (identifier, value) = parse_answer_pair(answer_match).unwrap();
-            if let Ok(value) = value.parse::<i64>() {
-                answers.insert(identifier.into(), value.into());
-            } else if let Ok(value) = value.parse::<bool>() {
-                answers.insert(identifier.into(), value.into());
-            } else {
-                answers.insert(identifier.into(), value.into());
-            }
-        }
-    }
+    answers.extend(command_line_answers(&matches)?);
 
     let archetect = Archetect::builder()
         .with_configuration(configuration)
@@ -96,6 +78,7 @@ fn execute<D: IoDriver, L: SystemLayout>(matches: ArgMatches, driver: D, layout:
         Some(("actions", args)) => handle_commands_subcommand(args, &archetect),
         Some(("render", args)) => render(args, archetect, answers)?,
         Some(("catalog", args)) => catalog(args, archetect, answers)?,
+        Some(("replay", args)) => replay(args, archetect)?,
         Some(("config", args)) => subcommands::handle_config_subcommand(args, &archetect)?,
         Some(("cache", args)) => subcommands::handle_cache_subcommand(args, &archetect)?,
         Some(("check", args)) => subcommands::handle_check_subcommand(args, &archetect)?,
@@ -110,6 +93,34 @@ fn execute<D: IoDriver, L: SystemLayout>(matches: ArgMatches, driver: D, layout:
     Ok(())
 }
 
+fn command_line_answers(matches: &ArgMatches) -> Result<Map, ArchetectError> {
+    let mut answers = Map::new();
+
+    // Load answers from answer files
+    if let Some(answer_files) = matches.get_many::<String>("answer-file") {
+        for answer_file in answer_files {
+            let results = answers::read_answers(answer_file)?;
+            answers.extend(results);
+        }
+    }
+
+    // Load answers from individual answer arguments
+    if let Some(answer_matches) = matches.get_many::<String>("answer") {
+        for answer_match in answer_matches {
+            let (identifier, value) = parse_answer_pair(answer_match).unwrap();
+            if let Ok(value) = value.parse::<i64>() {
+                answers.insert(identifier.into(), value.into());
+            } else if let Ok(value) = value.parse::<bool>() {
+                answers.insert(identifier.into(), value.into());
+            } else {
+                answers.insert(identifier.into(), value.into());
+            }
+        }
+    }
+
+    Ok(answers)
+}
+
 fn execute_action(matches: &ArgMatches, archetect: Archetect, answers: Map) -> Result<(), ArchetectError> {
     let action = matches.get_one::<String>("action").expect("Expected an action");
     match archetect.configuration().action(&action) {
@@ -125,21 +136,25 @@ fn execute_action(matches: &ArgMatches, archetect: Archetect, answers: Map) -> R
             ))
         }
         Some(command) => {
-            match command {
+            let render_context = match command {
                 ArchetectAction::RenderGroup{info, ..} => {
                     let catalog = Catalog::new(archetect.clone(), CatalogManifest::new().with_entries(info.actions().clone()));
                     let destination = shellexpand::full(matches.get_one::<String>("destination").expect("Enforced by Clap"))?.to_string();
                     let destination = Utf8PathBuf::from(destination);
                     let render_context = configure_render_context(RenderContext::new(destination, answers), &archetect, matches);
-                    catalog.render(render_context)?;
+                    let render_context = configure_output(render_context, matches)?;
+                    catalog.render(render_context.clone())?;
+                    render_context
                 }
                 ArchetectAction::RenderCatalog{info, ..} => {
                     let destination = shellexpand::full(matches.get_one::<String>("destination").expect("Enforced by Clap"))?.to_string();
                     let destination = Utf8PathBuf::from(destination);
                     let render_context = configure_render_context(RenderContext::new(destination, answers), &archetect, matches);
+                    let render_context = configure_output(render_context, matches)?;
                     let catalog = archetect.new_catalog(info.source())?;
                     catalog.check_requirements()?;
-                    catalog.render(render_context)?;
+                    catalog.render(render_context.clone())?;
+                    render_context
                 }
                 ArchetectAction::RenderArchetype{info, ..} => {
                     let destination = shellexpand::full(matches.get_one::<String>("destination").expect("Enforced by Clap"))?.to_string();
@@ -147,13 +162,14 @@ fn execute_action(matches: &ArgMatches, archetect: Archetect, answers: Map) -> R
                     let render_context = configure_render_context(RenderContext::new(destination, answers), &archetect, matches)
                         .with_archetype_info(&info)
                         ;
+                    let render_context = configure_output(render_context, matches)?;
                     let archetype = archetect.new_archetype(info.source())?;
                     archetype.check_requirements()?;
-                    let _ = archetype.render(render_context)?;
-
+                    let _ = archetype.render(render_context.clone())?;
+                    render_context
                 }
-            }
-            Ok(())
+            };
+            finish_render(matches, &archetect, &render_context)
         }
     }
 }
@@ -169,22 +185,84 @@ pub fn render(matches: &ArgMatches, archetect: Archetect, answers: Map) -> Resul
     let destination = shellexpand::full(matches.get_one::<String>("destination").expect("Enforced by Clap"))?.to_string();
     let destination = Utf8PathBuf::from(destination);
     let render_context = configure_render_context(RenderContext::new(destination, answers), &archetect, matches);
+    let render_context = configure_output(render_context, matches)?;
     match source.source_contents() {
         SourceContents::Archetype => {
-            let archetype = Archetype::new(archetect, source)?;
+            let archetype = Archetype::new(archetect.clone(), source)?;
             archetype.check_requirements()?;
-            Ok(archetype.render(render_context).map(|_| ())?)
+            let _ = archetype.render(render_context.clone())?;
         }
         SourceContents::Catalog => {
-           let catalog = Catalog::load(archetect, source)?;
+            let catalog = Catalog::load(archetect.clone(), source)?;
             catalog.check_requirements()?;
-            Ok(catalog.render(render_context)?)
+            catalog.render(render_context.clone())?;
         }
         SourceContents::Unknown => {
-            Err(SourceError::UnknownSourceContent.into())
+            return Err(SourceError::UnknownSourceContent.into());
         }
+    }
+    finish_render(matches, &archetect, &render_context)
+}
+
+pub fn replay(matches: &ArgMatches, archetect: Archetect) -> Result<(), ArchetectError> {
+    let destination = shellexpand::full(matches.get_one::<String>("destination").expect("Enforced by Clap"))?.to_string();
+    let destination = Utf8PathBuf::from(destination);
+    let record = RenderRecord::load(&destination)?;
+    let gitref = matches.get_one::<String>("gitref").map(|v| v.as_str());
+    let source = archetect.new_source(&record.replay_source(gitref))?;
+
+    // Answers given on the command line override recorded answers, which in turn override configured answers
+    let render_context = RenderContext::new(destination, command_line_answers(matches)?);
+    let render_context = record.apply_to(configure_render_context(render_context, &archetect, matches));
+    let mut answers = archetect.configuration().answers().clone();
+    answers.extend(render_context.answers_owned());
+    let render_context = configure_output(render_context.with_answers(answers), matches)?;
 
+    let archetype = Archetype::new(archetect.clone(), source)?;
+    archetype.check_requirements()?;
+    let _ = archetype.render(render_context.clone())?;
+    finish_render(matches, &archetect, &render_context)
+}
+
+/// Completes a render once the archetype or catalog has run, finishing its output, then summarizing and reporting it.
+fn finish_render(
+    matches: &ArgMatches,
+    archetect: &Archetect,
+    render_context: &RenderContext,
+) -> Result<(), ArchetectError> {
+    finish_output(render_context)?;
+    print_summary(matches, archetect, render_context);
+    write_report(matches, render_context)
+}
+
+fn print_summary(matches: &ArgMatches, archetect: &Archetect, render_context: &RenderContext) {
+    let plan = render_context.plan();
+    let conflicts = plan.conflicts();
+    if !conflicts.is_empty() {
+        let verb = if render_context.dry_run() { "would be left" } else { "were left" };
+        let mut summary = format!("Merge conflicts {} in {} file(s):", verb, conflicts.len());
+        for conflict in conflicts {
+            summary.push_str(&format!("\n  {}", conflict.destination()));
+        }
+        archetect.request(CommandRequest::LogWarn(summary));
+    }
+
+    if !render_context.dry_run() {
+        return;
     }
+    let output = match matches.get_one::<String>("plan-format").map(|v| v.as_str()) {
+        Some("json") => plan.to_json(render_context.destination()),
+        _ => plan.to_tree(render_context.destination()),
+    };
+    archetect.request(CommandRequest::Print(output));
+}
+
+fn write_report(matches: &ArgMatches, render_context: &RenderContext) -> Result<(), ArchetectError> {
+    if let Some(path) = matches.get_one::<String>("report") {
+        let path = Utf8PathBuf::from(shellexpand::full(path)?.to_string());
+        RenderReport::capture(render_context)?.write(path)?;
+    }
+    Ok(())
 }
 
 fn configure_render_context(
@@ -196,6 +274,30 @@ fn configure_render_context(
         .with_switches(get_switches(matches, archetect.configuration()))
         .with_use_defaults_all(matches.get_flag("use-defaults-all"))
         .with_use_defaults(get_defaults(matches))
+        .with_dry_run(matches.get_flag("dry-run"))
+        .with_transactional(!matches.get_flag("no-transaction"))
+        .with_record(!matches.get_flag("no-record"))
+        .with_git_init(matches.get_flag("git-init").then_some(true))
+}
+
+fn configure_output(render_context: RenderContext, matches: &ArgMatches) -> Result<RenderContext, ArchetectError> {
+    let (path, format) = if let Some(path) = matches.get_one::<String>("output-archive") {
+        let format = ArchiveFormat::from_path(path).expect("Enforced by Clap");
+        (path, format)
+    } else if let Some(path) = matches.get_one::<String>("output-zip") {
+        (path, ArchiveFormat::Zip)
+    } else {
+        return Ok(render_context);
+    };
+    let path = Utf8PathBuf::from(shellexpand::full(path)?.to_string());
+    let root = render_context.destination().to_path_buf();
+    Ok(render_context.with_sink(ArchiveSink::new(path, root, format)))
+}
+
+/// Completes the render's output, such as by writing out the archive requested with `--output-archive`.
+fn finish_output(render_context: &RenderContext) -> Result<(), ArchetectError> {
+    render_context.sink().finish()?;
+    Ok(())
 }
 
 fn get_switches(matches: &ArgMatches, configuration: &Configuration) -> HashSet<String> {
@@ -107,10 +107,19 @@ impl Archetect {
         &self.inner.layout
     }
 
+    /// Sends `command` to the IoDriver, unless it is a render event the IoDriver has not opted into.
     pub fn request(&self, command: CommandRequest) {
+        if command.is_render_event() && !self.render_events() {
+            return;
+        }
         self.inner.io_driver.send(command)
     }
 
+    /// Whether the IoDriver should be sent events as each directory and file of a render is dealt with.
+    pub fn render_events(&self) -> bool {
+        self.inner.io_driver.render_events()
+    }
+
     pub fn configuration(&self) -> &Configuration {
         &self.inner.configuration
     }
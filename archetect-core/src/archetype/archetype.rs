@@ -1,21 +1,32 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
 use std::sync::Arc;
 
-use camino::{Utf8Path, Utf8PathBuf};
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
 use content_inspector::ContentType;
-use log::{debug, trace};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use log::{debug, trace, warn};
+use rayon::prelude::*;
 use rhai::{Dynamic, EvalAltResult, Map, Scope};
+use serde::{Deserialize, Serialize};
 
-use archetect_api::CommandRequest;
-use archetect_inquire::Confirm;
+use archetect_api::{CommandRequest, CommandResponse, FileEventInfo, OverwritePromptInfo};
 use archetect_templating::Environment;
 
 use crate::Archetect;
 use crate::archetype::archetype_directory::ArchetypeDirectory;
-use crate::archetype::archetype_manifest::ArchetypeManifest;
+use crate::archetype::archetype_manifest::{ArchetypeManifest, LineEndings, PostRenderHook, SymlinkBehavior};
+use crate::archetype::git_init::initialize_repository;
+use crate::archetype::post_render::run_post_render_hooks;
 use crate::archetype::render_context::RenderContext;
+use crate::archetype::pristine::{merge_rendered, write_pristine, PristineStore};
+use crate::archetype::render_plan::{PlannedAction, PlannedFile};
+use crate::archetype::render_record::RenderRecord;
+use crate::archetype::render_rules::RenderRules;
+use crate::archetype::render_transaction::RenderTransaction;
 use crate::errors::{ArchetypeError, RenderError};
 use crate::script::create_environment;
 use crate::script::rhai::create_engine;
@@ -30,12 +41,22 @@ pub struct Archetype {
 pub(crate) struct Inner {
     source: Option<Source>,
     pub directory: ArchetypeDirectory,
+    rules: RenderRules,
 }
 
 impl Archetype {
     pub fn new(archetect: Archetect, source: Source) -> Result<Archetype, ArchetypeError> {
         let directory = ArchetypeDirectory::new(source.path()?)?;
-        let inner = Arc::new(Inner { directory, source: Some(source) });
+        let templating = directory.manifest().templating();
+        let rules = RenderRules::new(templating.rules())?.with_content_types(templating.binary(), templating.text())?;
+        for hook in directory.manifest().post_render() {
+            hook.check_directory()?;
+        }
+        let inner = Arc::new(Inner {
+            directory,
+            source: Some(source),
+            rules,
+        });
         let archetype = Archetype { archetect, inner };
 
         Ok(archetype)
@@ -57,6 +78,10 @@ impl Archetype {
         self.inner.directory.root()
     }
 
+    pub fn render_rules(&self) -> &RenderRules {
+        &self.inner.rules
+    }
+
     pub fn content_directory(&self) -> Utf8PathBuf {
         self.root().join(self.manifest().templating().content_directory())
     }
@@ -65,7 +90,83 @@ impl Archetype {
         self.root().join(self.manifest().templating().templates_directory())
     }
 
+    /// Renders this archetype into the context's destination.  When the context is transactional, and not already
+    /// participating in a transaction, all output is staged and only committed to the destination if the script
+    /// completes successfully.
     pub fn render(&self, render_context: RenderContext) -> Result<Dynamic, ArchetypeError> {
+        if let Some(source) = self.source() {
+            render_context.answer_log().set_source(source.source_type().source());
+        }
+        let render_context = match render_context.pristine() {
+            Some(_) => render_context,
+            None => {
+                let pristine = PristineStore::new(render_context.destination());
+                render_context.with_pristine(pristine)
+            }
+        };
+        if render_context.transactional()
+            && render_context.transaction().is_none()
+            && render_context.sink().is_filesystem()
+            && !render_context.dry_run()
+        {
+            let transaction = RenderTransaction::begin(render_context.destination())?;
+            let render_context = render_context.with_transaction(transaction.clone());
+            let result = self.render_script(render_context.clone())?;
+            self.write_record(&render_context)?;
+            render_context
+                .post_render()
+                .defer(render_context.destination(), self.post_render_hooks(&render_context));
+            transaction.commit()?;
+            for (destination, hooks) in render_context.post_render().take() {
+                run_post_render_hooks(&self.archetect, &render_context, &destination, &hooks)?;
+            }
+            self.initialize_repository(&render_context)?;
+            return Ok(result);
+        }
+        let result = self.render_script(render_context.clone())?;
+        self.write_record(&render_context)?;
+        let hooks = self.post_render_hooks(&render_context);
+        if render_context.transaction().is_some() {
+            render_context.post_render().defer(render_context.destination(), hooks);
+        } else {
+            run_post_render_hooks(&self.archetect, &render_context, render_context.destination(), &hooks)?;
+            self.initialize_repository(&render_context)?;
+        }
+        Ok(result)
+    }
+
+    /// The post-render hooks of this archetype enabled by the switches of `render_context`.
+    fn post_render_hooks(&self, render_context: &RenderContext) -> Vec<PostRenderHook> {
+        self.manifest()
+            .post_render()
+            .iter()
+            .filter(|hook| match hook.if_switch() {
+                Some(switch) => render_context.switches().contains(switch),
+                None => true,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Initializes a git repository in the destination if requested by `render_context`, or otherwise by this
+    /// archetype's `git.init` setting.
+    fn initialize_repository(&self, render_context: &RenderContext) -> Result<(), ArchetypeError> {
+        let git = self.manifest().git();
+        if render_context.git_init().unwrap_or(git.init()) {
+            initialize_repository(&self.archetect, render_context, render_context.destination(), git.message())?;
+        }
+        Ok(())
+    }
+
+    fn write_record(&self, render_context: &RenderContext) -> Result<(), ArchetypeError> {
+        if render_context.record() && !render_context.dry_run() {
+            let record = RenderRecord::capture(&self.archetect, self, render_context);
+            record.write(render_context)?;
+        }
+        Ok(())
+    }
+
+    fn render_script(&self, render_context: RenderContext) -> Result<Dynamic, ArchetypeError> {
         let mut scope = Scope::new();
         scope.push_constant("ANSWERS", render_context.answers_owned());
         scope.push_constant("SWITCHES", render_context.switches_as_array());
@@ -104,9 +205,68 @@ impl Archetype {
     }
 }
 
+/// Renders the contents of `source` into `destination`.  Each file is rendered, copied, or skipped according to the
+/// first of the archetype's templating rules matching its path relative to `source`, falling back to copying binary
+/// files and rendering everything else.
+///
+/// The tree is walked once to create directories and collect files, whose contents are then read, rendered, and
+/// written across a pool of workers.  Files needing a decision about existing contents, such as a prompt or a
+/// merge, are resolved afterwards one at a time.  Files are always resolved, recorded in the render plan, and have
+/// their errors reported in path order, regardless of the order in which the workers finish.
+#[allow(clippy::too_many_arguments)]
 pub fn render_directory<SRC: Into<Utf8PathBuf>, DEST: Into<Utf8PathBuf>>(
     environment: &Environment<'static>,
     archetect: &Archetect,
+    archetype: &Archetype,
+    render_context: &RenderContext,
+    context: &Map,
+    source: SRC,
+    destination: DEST,
+    overwrite_policy: OverwritePolicy,
+) -> Result<(), RenderError> {
+    let source = source.into();
+    let walk = DirectoryWalk {
+        environment,
+        rules: archetype.render_rules(),
+        symlinks: archetype.manifest().templating().symlinks(),
+        template_suffixes: archetype.manifest().templating().template_suffixes(),
+        render_context,
+        context,
+        root: &source,
+        created: Default::default(),
+    };
+    let mut files = vec![];
+    let destination = destination.into();
+    let mut ignores = vec![ignore_patterns(&source, archetype.manifest().templating().ignore())?];
+    walk.create_dir(&destination)?;
+    walk.walk(&source, &destination, &mut files, &mut ignores)?;
+    for directory in walk.created.take() {
+        archetect.request(CommandRequest::DirectoryCreated(directory.to_string()));
+    }
+
+    let renderer = FileRenderer {
+        environment,
+        render_context,
+        context,
+        rules: archetype.render_rules(),
+        line_endings: archetype.manifest().templating().line_endings(),
+        trailing_newline: archetype.manifest().templating().trailing_newline(),
+        max_render_size: archetype.manifest().templating().max_render_size(),
+        overwrite_policy,
+        headless: archetect.is_headless(),
+    };
+    renderer.render_all(archetect, files)
+}
+
+/// Renders the single file at `source`, which must be within the archetype's content or templates directory, to
+/// `destination`.  The file is rendered, copied, or skipped according to the first of the archetype's templating
+/// rules matching its path relative to that directory, and is otherwise handled like any file of a directory render.
+#[allow(clippy::too_many_arguments)]
+pub fn render_file<SRC: Into<Utf8PathBuf>, DEST: Into<Utf8PathBuf>>(
+    environment: &Environment<'static>,
+    archetect: &Archetect,
+    archetype: &Archetype,
+    render_context: &RenderContext,
     context: &Map,
     source: SRC,
     destination: DEST,
@@ -114,102 +274,675 @@ pub fn render_directory<SRC: Into<Utf8PathBuf>, DEST: Into<Utf8PathBuf>>(
 ) -> Result<(), RenderError> {
     let source = source.into();
     let destination = destination.into();
-    if !destination.exists() {
-        fs::create_dir_all(&destination)
-            .map_err(|err| RenderError::CreateDirectoryError { path: destination.to_path_buf(), source: err })
-            ?
-    }
-
-    for entry in fs::read_dir(&source)
-        .map_err(|err| RenderError::DirectoryListError { path: source.to_path_buf(), source: err })? {
-        let entry = entry
-            .map_err(|err| RenderError::DirectoryReadError { path: source.clone(), source: err })
-            ?;
-        let path = Utf8PathBuf::from_path_buf(entry.path()).unwrap();
-
-        if path.is_dir() {
-            let destination = render_destination(environment, context, &destination, &path)?;
-            fs::create_dir_all(destination.as_path())
-                .map_err(|err| RenderError::CreateDirectoryError { path: path.clone(), source: err })
-                ?;
-            render_directory(
-                environment,
-                archetect,
-                context,
-                path,
-                destination,
-                overwrite_policy,
-            )?;
-        } else if path.is_file() {
-            // TODO: avoid duplication of file read
-            let contents = fs::read(&path)
-                .map_err(|err| RenderError::FileReadError { path: path.to_path_buf(), source: err })?;
-
-            let action = match content_inspector::inspect(contents.as_slice()) {
-                ContentType::BINARY => RuleAction::COPY,
-                _ => RuleAction::RENDER,
+    let root = [archetype.content_directory(), archetype.template_directory()]
+        .into_iter()
+        .find(|root| source.starts_with(root))
+        .unwrap_or_else(|| archetype.root().to_path_buf());
+    let walk = DirectoryWalk {
+        environment,
+        rules: archetype.render_rules(),
+        symlinks: archetype.manifest().templating().symlinks(),
+        template_suffixes: archetype.manifest().templating().template_suffixes(),
+        render_context,
+        context,
+        root: &root,
+        created: Default::default(),
+    };
+    let relative = source.strip_prefix(&root).unwrap_or(&source).to_path_buf();
+    let action = archetype.render_rules().action(&relative, render_context.switches());
+    let (_, action) = walk.apply_template_suffixes(&source, action);
+    if action != Some(RuleAction::SKIP) {
+        walk.create_parent(&destination)?;
+    }
+    for directory in walk.created.take() {
+        archetect.request(CommandRequest::DirectoryCreated(directory.to_string()));
+    }
+
+    let renderer = FileRenderer {
+        environment,
+        render_context,
+        context,
+        rules: archetype.render_rules(),
+        line_endings: archetype.manifest().templating().line_endings(),
+        trailing_newline: archetype.manifest().templating().trailing_newline(),
+        max_render_size: archetype.manifest().templating().max_render_size(),
+        overwrite_policy,
+        headless: archetect.is_headless(),
+    };
+    let file = FileEntry {
+        source,
+        destination,
+        relative,
+        action,
+        link: None,
+    };
+    renderer.render_all(archetect, vec![file])
+}
+
+fn read_source(file: &FileEntry) -> Result<Vec<u8>, RenderError> {
+    fs::read(&file.source).map_err(|err| RenderError::FileReadError { path: file.source.clone(), source: err })
+}
+
+fn source_size(file: &FileEntry) -> Result<u64, RenderError> {
+    fs::metadata(&file.source)
+        .map(|metadata| metadata.len())
+        .map_err(|err| RenderError::FileReadError { path: file.source.clone(), source: err })
+}
+
+/// The render event reporting that `planned` was carried out for `file`, if it was written or deliberately preserved.
+/// A file is preserved after being `prompted` for when overwriting it was declined.
+fn file_event(file: &FileEntry, planned: PlannedAction, prompted: bool) -> Option<CommandRequest> {
+    let info = || FileEventInfo::new(file.destination.as_str(), file.source.as_str());
+    match planned {
+        PlannedAction::Create
+        | PlannedAction::Overwrite
+        | PlannedAction::Merge
+        | PlannedAction::Conflict
+        | PlannedAction::Edit => Some(CommandRequest::FileRendered(info())),
+        PlannedAction::Copy => Some(CommandRequest::FileCopied(info())),
+        PlannedAction::Preserve if prompted => Some(CommandRequest::OverwriteSkipped(info())),
+        PlannedAction::Preserve => Some(CommandRequest::FilePreserved(info())),
+        PlannedAction::Prompt | PlannedAction::Skip => None,
+    }
+}
+
+/// Gitignore-style files listing paths within a content directory to leave out of a render, relative to the directory
+/// containing them.
+pub const IGNORE_FILE: &str = ".archetectignore";
+
+/// Compiles gitignore-style `patterns` relative to `root`.
+fn ignore_patterns(root: &Utf8Path, patterns: &[String]) -> Result<Gitignore, RenderError> {
+    let mut builder = GitignoreBuilder::new(root);
+    for pattern in patterns {
+        builder
+            .add_line(None, pattern)
+            .map_err(|err| RenderError::IgnoreError { path: root.to_path_buf(), source: err })?;
+    }
+    builder
+        .build()
+        .map_err(|err| RenderError::IgnoreError { path: root.to_path_buf(), source: err })
+}
+
+/// Whether `path` is ignored, with the innermost matching pattern taking precedence so that nested ignore files can
+/// re-include paths ignored further up.
+fn is_ignored(ignores: &[Gitignore], path: &Utf8Path) -> bool {
+    let is_dir = path.is_dir();
+    ignores
+        .iter()
+        .rev()
+        .map(|ignore| ignore.matched(path, is_dir))
+        .find(|matched| !matched.is_none())
+        .map(|matched| matched.is_ignore())
+        .unwrap_or_default()
+}
+
+/// A file found while walking a directory, along with where it renders to.
+struct FileEntry {
+    source: Utf8PathBuf,
+    destination: Utf8PathBuf,
+    relative: Utf8PathBuf,
+    action: Option<RuleAction>,
+    /// The target to link the destination to, if the source is a symbolic link to be recreated
+    link: Option<Utf8PathBuf>,
+}
+
+enum RenderedFile {
+    /// The file has been dealt with
+    Done(PlannedAction),
+    /// The file rendered to contents that must be reconciled with a file already at its destination
+    Existing(String),
+    /// The file is to be copied over a file already at its destination, once overwriting it has been confirmed
+    ExistingCopy,
+}
+
+struct DirectoryWalk<'a> {
+    environment: &'a Environment<'static>,
+    rules: &'a RenderRules,
+    symlinks: SymlinkBehavior,
+    template_suffixes: &'a [String],
+    render_context: &'a RenderContext,
+    context: &'a Map,
+    root: &'a Utf8Path,
+    /// Directories created by the walk, in the order they were created
+    created: RefCell<Vec<Utf8PathBuf>>,
+}
+
+impl DirectoryWalk<'_> {
+    /// Creates the directories beneath `source` at their rendered destinations, and collects the files within them
+    /// in path order.  Paths matched by `ignores`, or by an `.archetectignore` file within the tree, are left out.
+    /// Returns whether the directory at `destination` was kept.
+    fn walk(
+        &self,
+        source: &Utf8Path,
+        destination: &Utf8Path,
+        files: &mut Vec<FileEntry>,
+        ignores: &mut Vec<Gitignore>,
+    ) -> Result<bool, RenderError> {
+        let ignore_file = source.join(IGNORE_FILE);
+        let scoped = ignore_file.is_file();
+        if scoped {
+            let (ignore, error) = Gitignore::new(&ignore_file);
+            if let Some(error) = error {
+                return Err(RenderError::IgnoreError { path: ignore_file, source: error });
+            }
+            ignores.push(ignore);
+        }
+        let result = self.walk_entries(source, destination, files, ignores);
+        if scoped {
+            ignores.pop();
+        }
+        result
+    }
+
+    /// A directory is only created once something within it is, so that directories whose contents are all skipped
+    /// are left out, while empty directories are still created.
+    fn walk_entries(
+        &self,
+        source: &Utf8Path,
+        destination: &Utf8Path,
+        files: &mut Vec<FileEntry>,
+        ignores: &mut Vec<Gitignore>,
+    ) -> Result<bool, RenderError> {
+        let render_context = self.render_context;
+        let mut paths = vec![];
+        for entry in fs::read_dir(source)
+            .map_err(|err| RenderError::DirectoryListError { path: source.to_path_buf(), source: err })?
+        {
+            let entry =
+                entry.map_err(|err| RenderError::DirectoryReadError { path: source.to_path_buf(), source: err })?;
+            paths.push(Utf8PathBuf::from_path_buf(entry.path()).unwrap());
+        }
+        paths.sort();
+
+        let mut kept = false;
+        let mut skipped = false;
+        for path in paths {
+            if path.file_name() == Some(IGNORE_FILE) || is_ignored(ignores, &path) {
+                trace!("Ignoring    {:?}", path);
+                continue;
+            }
+            let relative = path.strip_prefix(self.root).unwrap_or(&path).to_path_buf();
+            let action = self.rules.action(&relative, render_context.switches());
+
+            if path.is_symlink() && self.symlinks != SymlinkBehavior::Dereference {
+                if let Some(RuleAction::SKIP) = action {
+                    trace!("Skipping    {:?}", path);
+                    skipped = true;
+                    continue;
+                }
+                let target = path
+                    .read_link_utf8()
+                    .map_err(|err| RenderError::FileReadError { path: path.clone(), source: err })?;
+                let target = match self.symlinks {
+                    SymlinkBehavior::Render => {
+                        Utf8PathBuf::from(render_template(self.environment, self.context, &path, target.as_str())?)
+                    }
+                    _ => target,
+                };
+                let Some(destination) = render_destination(self.environment, self.context, destination, &path)? else {
+                    trace!("Excluding   {:?}", path);
+                    skipped = true;
+                    continue;
+                };
+                restrict_link_target(render_context.destination(), &destination, &path, &target)?;
+                self.create_parent(&destination)?;
+                kept = true;
+                files.push(FileEntry {
+                    destination,
+                    source: path,
+                    relative,
+                    action,
+                    link: Some(target),
+                });
+            } else if path.is_dir() {
+                if let Some(RuleAction::SKIP) = action {
+                    trace!("Skipping    {:?}", path);
+                    skipped = true;
+                    continue;
+                }
+                let Some(destination) = render_destination(self.environment, self.context, destination, &path)? else {
+                    trace!("Excluding   {:?}", path);
+                    skipped = true;
+                    continue;
+                };
+                if self.walk(&path, &destination, files, ignores)? {
+                    kept = true;
+                } else {
+                    skipped = true;
+                }
+            } else if path.is_file() {
+                let unrendered = || destination.join(path.file_name().unwrap_or_default());
+                let (template, action) = self.apply_template_suffixes(&path, action);
+                let (destination, action) = match action {
+                    // Skipped files may be named after answers that are only provided when they would be rendered
+                    Some(RuleAction::SKIP) => (
+                        render_destination(self.environment, self.context, destination, &path)
+                            .ok()
+                            .flatten()
+                            .unwrap_or_else(unrendered),
+                        action,
+                    ),
+                    _ => match render_destination(self.environment, self.context, destination, &template)? {
+                        Some(destination) => (destination, action),
+                        None => (unrendered(), Some(RuleAction::SKIP)),
+                    },
+                };
+                if action == Some(RuleAction::SKIP) {
+                    skipped = true;
+                } else {
+                    self.create_parent(&destination)?;
+                    kept = true;
+                }
+                files.push(FileEntry {
+                    source: path,
+                    destination,
+                    relative,
+                    action,
+                    link: None,
+                });
+            } else {
+                warn!("Skipping {:?}, which is neither a file nor a directory", path);
+            }
+        }
+
+        if skipped && !kept {
+            trace!("Skipping    {:?}, as everything within it was skipped", source);
+            return Ok(false);
+        }
+        self.create_dir(destination)?;
+        Ok(true)
+    }
+
+    /// When the archetype uses template suffixes, files ending in one are rendered under their name without it, and
+    /// all others are copied, unless a templating rule says otherwise.
+    fn apply_template_suffixes(
+        &self,
+        path: &Utf8Path,
+        action: Option<RuleAction>,
+    ) -> (Utf8PathBuf, Option<RuleAction>) {
+        if self.template_suffixes.is_empty() {
+            return (path.to_path_buf(), action);
+        }
+        let name = path.file_name().unwrap_or_default();
+        let stripped = self
+            .template_suffixes
+            .iter()
+            .filter_map(|suffix| name.strip_suffix(suffix.as_str()))
+            .find(|stripped| !stripped.is_empty());
+        match (stripped, action) {
+            (Some(stripped), None | Some(RuleAction::RENDER)) => {
+                (path.with_file_name(stripped), Some(RuleAction::RENDER))
+            }
+            (None, None) => (path.to_path_buf(), Some(RuleAction::COPY)),
+            (_, action) => (path.to_path_buf(), action),
+        }
+    }
+
+    /// Creates the directories leading to a file whose name rendered into a nested path.
+    fn create_parent(&self, destination: &Utf8Path) -> Result<(), RenderError> {
+        match destination.parent() {
+            Some(parent) => self.create_dir(parent),
+            None => Ok(()),
+        }
+    }
+
+    /// Creates `directory`, along with any missing parents, recording each directory created.
+    fn create_dir(&self, directory: &Utf8Path) -> Result<(), RenderError> {
+        let render_context = self.render_context;
+        if render_context.dry_run() || render_context.output_exists(directory) {
+            return Ok(());
+        }
+        let missing = directory
+            .ancestors()
+            .take_while(|ancestor| !ancestor.as_str().is_empty() && !render_context.output_exists(ancestor))
+            .map(Utf8Path::to_path_buf)
+            .collect::<Vec<_>>();
+        render_context.create_output_dir(directory)?;
+        self.created.borrow_mut().extend(missing.into_iter().rev());
+        Ok(())
+    }
+}
+
+struct FileRenderer<'a> {
+    environment: &'a Environment<'static>,
+    render_context: &'a RenderContext,
+    context: &'a Map,
+    rules: &'a RenderRules,
+    line_endings: LineEndings,
+    trailing_newline: bool,
+    max_render_size: Option<u64>,
+    overwrite_policy: OverwritePolicy,
+    headless: bool,
+}
+
+impl FileRenderer<'_> {
+    /// Renders `files` across a pool of workers, then resolves those needing a decision about existing contents one
+    /// at a time, recording each in the render plan in the order given.
+    fn render_all(&self, archetect: &Archetect, files: Vec<FileEntry>) -> Result<(), RenderError> {
+        let render_context = self.render_context;
+        let results = files
+            .par_iter()
+            .map(|file| self.render(file))
+            .collect::<Vec<Result<RenderedFile, RenderError>>>();
+
+        for (file, result) in files.into_iter().zip(results) {
+            let (planned, prompted) = match result? {
+                RenderedFile::Done(planned) => (planned, false),
+                RenderedFile::Existing(contents) => (
+                    self.resolve_existing(archetect, &file, &contents)?,
+                    !matches!(self.overwrite_policy, OverwritePolicy::Merge),
+                ),
+                RenderedFile::ExistingCopy => (self.resolve_copy(archetect, &file)?, true),
             };
+            if !render_context.dry_run() {
+                if let Some(event) = file_event(&file, planned, prompted) {
+                    archetect.request(event);
+                }
+            }
+            render_context
+                .plan()
+                .record(PlannedFile::new(file.destination, file.source, planned));
+        }
+
+        Ok(())
+    }
+
+    /// Renders or copies `file`, reading it at most once, unless it already exists at its destination and the
+    /// overwrite policy calls for a decision.
+    fn render(&self, file: &FileEntry) -> Result<RenderedFile, RenderError> {
+        let render_context = self.render_context;
+        let dry_run = render_context.dry_run();
+        let destination = &file.destination;
+
+        if let Some(target) = &file.link {
+            return self.render_link(destination, target);
+        }
 
-            let destination = render_destination(environment, context, &destination, &path)?;
-            match action {
-                RuleAction::RENDER => {
-                    if !destination.exists() {
-                        debug!("Rendering {:?}", destination);
-                        let contents = render_contents(environment, context, &path)?;
-                        write_contents(destination, &contents)?;
-                    } else {
-                        match overwrite_policy {
-                            OverwritePolicy::Overwrite => {
-                                debug!("Overwriting {:?}", destination);
-                                let contents = render_contents(environment, context, &path)?;
-                                write_contents(destination, &contents)?;
-                            }
-                            OverwritePolicy::Preserve => {
-                                trace!("Preserving {:?}", destination);
-                            }
-                            OverwritePolicy::Prompt => {
-                                if archetect.is_headless() {
-                                    trace!("Preserving {:?}", destination);
-                                } else {
-                                    if Confirm::new(format!("Overwrite '{}'?", destination).as_str())
-                                        .prompt_skippable()
-                                        .unwrap_or_default()
-                                        .unwrap_or_default()
-                                    {
-                                        debug!("Overwriting {:?}", destination);
-                                        let contents = render_contents(environment, context, &path)?;
-                                        write_contents(destination, &contents)?;
-                                    }
-                                }
-                            }
-                        }
+        let contents = match file.action {
+            Some(RuleAction::SKIP) => {
+                trace!("Skipping    {:?}", destination);
+                return Ok(RenderedFile::Done(PlannedAction::Skip));
+            }
+            Some(RuleAction::COPY) => None,
+            Some(RuleAction::RENDER) => Some(read_source(file)?),
+            None if self.rules.is_binary(&file.relative) => None,
+            None if self.rules.is_text(&file.relative) => Some(read_source(file)?),
+            None => match self.max_render_size {
+                Some(max_render_size) if source_size(file)? > max_render_size => {
+                    warn!(
+                        "Copying {:?} without rendering, as it is larger than the maximum renderable size of {} bytes",
+                        file.source, max_render_size
+                    );
+                    None
+                }
+                _ => {
+                    let contents = read_source(file)?;
+                    match content_inspector::inspect(contents.as_slice()) {
+                        ContentType::BINARY => None,
+                        _ => Some(contents),
                     }
                 }
-                RuleAction::COPY => {
-                    debug!("Copying     {:?}", destination);
-                    copy_contents(&path, &destination)?;
+            },
+        };
+
+        let exists = render_context.output_exists(destination);
+        if exists {
+            match self.overwrite_policy {
+                OverwritePolicy::Preserve => {
+                    trace!("Preserving {:?}", destination);
+                    return Ok(RenderedFile::Done(PlannedAction::Preserve));
+                }
+                OverwritePolicy::Prompt if self.headless => {
+                    trace!("Preserving {:?}", destination);
+                    return Ok(RenderedFile::Done(PlannedAction::Preserve));
+                }
+                OverwritePolicy::Prompt if dry_run => {
+                    return Ok(RenderedFile::Done(PlannedAction::Prompt));
                 }
-                RuleAction::SKIP => {
-                    trace!("Skipping    {:?}", destination);
+                _ => (),
+            }
+        }
+
+        let Some(contents) = contents else {
+            // Copied files cannot be merged, so existing files are prompted for under the Merge policy as well
+            if exists && !matches!(self.overwrite_policy, OverwritePolicy::Overwrite) {
+                if self.headless {
+                    trace!("Preserving {:?}", destination);
+                    return Ok(RenderedFile::Done(PlannedAction::Preserve));
+                }
+                if dry_run {
+                    return Ok(RenderedFile::Done(PlannedAction::Prompt));
+                }
+                return Ok(RenderedFile::ExistingCopy);
+            }
+            debug!("Copying     {:?}", destination);
+            if !dry_run {
+                render_context.copy_output(&file.source, destination)?;
+                self.apply_permissions(file)?;
+            }
+            return Ok(RenderedFile::Done(PlannedAction::Copy));
+        };
+
+        let template = String::from_utf8(contents).map_err(|err| RenderError::FileRenderIOError {
+            path: file.source.clone(),
+            source: std::io::Error::new(std::io::ErrorKind::InvalidData, err),
+        })?;
+        let contents = render_template(self.environment, self.context, &file.source, &template)?;
+        let contents = self.normalize(file, contents);
+
+        let planned = match (exists, self.overwrite_policy) {
+            (false, _) => {
+                debug!("Rendering {:?}", destination);
+                PlannedAction::Create
+            }
+            (true, OverwritePolicy::Overwrite) => {
+                debug!("Overwriting {:?}", destination);
+                PlannedAction::Overwrite
+            }
+            (true, _) => return Ok(RenderedFile::Existing(contents)),
+        };
+        if !dry_run {
+            render_context.write_output(destination, contents.as_bytes())?;
+            write_pristine(render_context, destination, &contents)?;
+            self.apply_permissions(file)?;
+        }
+        Ok(RenderedFile::Done(planned))
+    }
+
+    /// Reconciles the rendered `contents` of `file` with the file already at its destination, by prompting or
+    /// merging according to the overwrite policy.
+    fn resolve_existing(
+        &self,
+        archetect: &Archetect,
+        file: &FileEntry,
+        contents: &str,
+    ) -> Result<PlannedAction, RenderError> {
+        let render_context = self.render_context;
+        let destination = &file.destination;
+        let planned = match self.overwrite_policy {
+            OverwritePolicy::Merge => merge_rendered(render_context, destination, contents)?,
+            _ => {
+                if prompt_overwrite(archetect, render_context, destination, contents)? {
+                    debug!("Overwriting {:?}", destination);
+                    render_context.write_output(destination, contents.as_bytes())?;
+                    write_pristine(render_context, destination, contents)?;
+                    PlannedAction::Overwrite
+                } else {
+                    trace!("Preserving {:?}", destination);
+                    PlannedAction::Preserve
                 }
             }
+        };
+        if !render_context.dry_run() && planned.writes() {
+            self.apply_permissions(file)?;
         }
+        Ok(planned)
     }
 
-    Ok(())
+    /// Copies `file` over the file already at its destination if overwriting it is confirmed.
+    fn resolve_copy(&self, archetect: &Archetect, file: &FileEntry) -> Result<PlannedAction, RenderError> {
+        let render_context = self.render_context;
+        let destination = &file.destination;
+        let proposed = read_source(file)?;
+        if prompt_overwrite(archetect, render_context, destination, &String::from_utf8_lossy(&proposed))? {
+            debug!("Copying     {:?}", destination);
+            render_context.copy_output(&file.source, destination)?;
+            self.apply_permissions(file)?;
+            Ok(PlannedAction::Copy)
+        } else {
+            trace!("Preserving {:?}", destination);
+            Ok(PlannedAction::Preserve)
+        }
+    }
+
+    fn render_link(&self, destination: &Utf8Path, target: &Utf8Path) -> Result<RenderedFile, RenderError> {
+        let render_context = self.render_context;
+        // Links are checked for without being followed, as their targets may not exist
+        let exists = render_context.output_exists(destination)
+            || render_context.existing_path(destination).symlink_metadata().is_ok();
+        let planned = match (exists, self.overwrite_policy) {
+            (false, _) => PlannedAction::Create,
+            (true, OverwritePolicy::Overwrite) => PlannedAction::Overwrite,
+            (true, _) => {
+                trace!("Preserving {:?}", destination);
+                return Ok(RenderedFile::Done(PlannedAction::Preserve));
+            }
+        };
+        debug!("Linking     {:?} -> {:?}", destination, target);
+        if !render_context.dry_run() {
+            render_context.link_output(target, destination)?;
+        }
+        Ok(RenderedFile::Done(planned))
+    }
+
+    /// Applies the line ending and trailing newline settings for `file` to its rendered contents.
+    fn normalize(&self, file: &FileEntry, contents: String) -> String {
+        let switches = self.render_context.switches();
+        let line_endings = self.rules.line_endings(&file.relative, switches).unwrap_or(self.line_endings);
+        let trailing_newline = self
+            .rules
+            .trailing_newline(&file.relative, switches)
+            .unwrap_or(self.trailing_newline);
+        match line_endings.normalize(&contents, trailing_newline) {
+            Cow::Borrowed(_) => contents,
+            Cow::Owned(normalized) => normalized,
+        }
+    }
+
+    /// Gives the output of `file` the mode given by the templating rules, if any, or otherwise the mode of its source.
+    fn apply_permissions(&self, file: &FileEntry) -> Result<(), RenderError> {
+        let mode = self.rules.mode(&file.relative, self.render_context.switches());
+        match file_mode(&file.source, mode)? {
+            Some(mode) => self.render_context.set_output_mode(&file.destination, mode),
+            None => Ok(()),
+        }
+    }
 }
 
+/// Asks through the IoDriver whether the existing file at `destination` should be overwritten with `proposed`,
+/// unless an earlier prompt was answered for all remaining files.
+fn prompt_overwrite(
+    archetect: &Archetect,
+    render_context: &RenderContext,
+    destination: &Utf8Path,
+    proposed: &str,
+) -> Result<bool, RenderError> {
+    if let Some(overwrite) = render_context.overwrite_all() {
+        return Ok(overwrite);
+    }
+    let existing = render_context.read_existing(destination)?;
+    let prompt_info = OverwritePromptInfo::new(destination.as_str(), String::from_utf8_lossy(&existing), proposed);
+
+    archetect.request(CommandRequest::PromptForOverwrite(prompt_info));
+    match archetect.response() {
+        CommandResponse::Overwrite(decision) => {
+            if decision.applies_to_all() {
+                render_context.set_overwrite_all(decision.overwrite());
+            }
+            Ok(decision.overwrite())
+        }
+        CommandResponse::None => Ok(false),
+        CommandResponse::Abort => Err(RenderError::OperationInterrupted),
+        CommandResponse::Error(error) => Err(RenderError::OverwritePromptError {
+            path: destination.to_path_buf(),
+            message: error,
+        }),
+        response => Err(RenderError::OverwritePromptError {
+            path: destination.to_path_buf(),
+            message: format!("Unexpected response: {:?}", response),
+        }),
+    }
+}
+
+/// A name that excludes a file or directory from a render when its name renders to it, as an alternative to
+/// rendering an empty name.
+pub const EXCLUDED_NAME: &str = "__exclude__";
+
+/// The destination of `child` within `parent`, or `None` if its name renders blank or to [EXCLUDED_NAME], which
+/// excludes it and everything beneath it from the render.  A name rendering to a relative path, such as
+/// `com/acme/billing`, expands into nested directories, while names that would escape `parent` are rejected.
 fn render_destination<P: AsRef<Utf8Path>, C: AsRef<Utf8Path>>(
     environment: &Environment<'static>,
     context: &Map,
     parent: P,
     child: C,
-) -> Result<Utf8PathBuf, RenderError> {
+) -> Result<Option<Utf8PathBuf>, RenderError> {
     let child = child.as_ref();
     let name = render_path(environment, context, child)?;
+    if name.trim().is_empty() || name.trim() == EXCLUDED_NAME {
+        return Ok(None);
+    }
     let mut destination = parent.as_ref().to_owned();
-    destination.push(name);
-    Ok(destination)
+    for component in Utf8Path::new(&name).components() {
+        match component {
+            Utf8Component::Normal(segment) => destination.push(segment),
+            Utf8Component::CurDir => (),
+            Utf8Component::ParentDir | Utf8Component::RootDir | Utf8Component::Prefix(_) => {
+                return Err(RenderError::PathManipulationError {
+                    path: child.to_path_buf(),
+                    rendered: name,
+                });
+            }
+        }
+    }
+    if name.starts_with('~') || destination == parent.as_ref() {
+        return Err(RenderError::PathManipulationError {
+            path: child.to_path_buf(),
+            rendered: name,
+        });
+    }
+    Ok(Some(destination))
+}
+
+/// Ensures that `target`, the target of the link at `destination` recreated from `source`, is neither absolute nor
+/// resolves to a path outside of `root`.
+fn restrict_link_target(
+    root: &Utf8Path,
+    destination: &Utf8Path,
+    source: &Utf8Path,
+    target: &Utf8Path,
+) -> Result<(), RenderError> {
+    let error = || RenderError::LinkTargetError {
+        path: source.to_path_buf(),
+        target: target.to_path_buf(),
+    };
+    let mut depth = destination
+        .parent()
+        .and_then(|parent| parent.strip_prefix(root).ok())
+        .map(|parent| parent.components().count())
+        .ok_or_else(error)?;
+    if target.as_str().starts_with('~') {
+        return Err(error());
+    }
+    for component in target.components() {
+        match component {
+            Utf8Component::Normal(_) => depth += 1,
+            Utf8Component::CurDir => (),
+            Utf8Component::ParentDir => depth = depth.checked_sub(1).ok_or_else(error)?,
+            Utf8Component::RootDir | Utf8Component::Prefix(_) => return Err(error()),
+        }
+    }
+    Ok(())
 }
 
 fn render_path<P: AsRef<Utf8Path>>(
@@ -243,7 +976,16 @@ pub fn render_contents<P: AsRef<Utf8Path>>(
             });
         }
     };
-    match environment.render_str(&template, context) {
+    render_template(environment, context, path, &template)
+}
+
+fn render_template(
+    environment: &Environment<'static>,
+    context: &Map,
+    path: &Utf8Path,
+    template: &str,
+) -> Result<String, RenderError> {
+    match environment.render_str(template, context) {
         Ok(result) => Ok(result),
         Err(error) => Err(RenderError::PathRenderError2 {
             path: path.into(),
@@ -263,6 +1005,52 @@ pub fn write_contents<P: AsRef<Utf8Path>>(destination: P, contents: &str) -> Res
     Ok(())
 }
 
+/// The explicit `mode`, if any, or otherwise the permission bits of the `source` a file was rendered from if it is
+/// executable, made writable by its owner.  Files that are neither given a mode nor executable keep whatever mode they
+/// were created with.
+#[cfg(unix)]
+pub fn file_mode<S: AsRef<Utf8Path>>(source: S, mode: Option<u32>) -> Result<Option<u32>, RenderError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let source = source.as_ref();
+    if mode.is_some() {
+        return Ok(mode);
+    }
+    let mode = fs::metadata(source)
+        .map_err(|err| RenderError::FileReadError { path: source.to_path_buf(), source: err })?
+        .permissions()
+        .mode();
+    if mode & 0o111 == 0 {
+        return Ok(None);
+    }
+    Ok(Some((mode & 0o777) | 0o200))
+}
+
+/// Permissions are left to the platform where modes are not supported.
+#[cfg(not(unix))]
+pub fn file_mode<S: AsRef<Utf8Path>>(_source: S, _mode: Option<u32>) -> Result<Option<u32>, RenderError> {
+    Ok(None)
+}
+
+/// Creates a symbolic link at `link` pointing to `target`.
+pub fn create_symlink<T: AsRef<Utf8Path>, L: AsRef<Utf8Path>>(target: T, link: L) -> Result<(), RenderError> {
+    let target = target.as_ref();
+    let link = link.as_ref();
+    #[cfg(unix)]
+    let result = std::os::unix::fs::symlink(target, link);
+    #[cfg(windows)]
+    let result = if link.parent().map(|parent| parent.join(target).is_dir()).unwrap_or_default() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    };
+    result.map_err(|err| RenderError::SymlinkError {
+        link: link.to_path_buf(),
+        target: target.to_path_buf(),
+        source: err,
+    })
+}
+
 pub fn copy_contents<S: AsRef<Utf8Path>, D: AsRef<Utf8Path>>(source: S, destination: D) -> Result<(), RenderError> {
     let source = source.as_ref();
     let destination = destination.as_ref();
@@ -272,6 +1060,8 @@ pub fn copy_contents<S: AsRef<Utf8Path>, D: AsRef<Utf8Path>>(source: S, destinat
     Ok(())
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum RuleAction {
     COPY,
     RENDER,
@@ -283,6 +1073,7 @@ pub enum OverwritePolicy {
     Overwrite,
     Preserve,
     Prompt,
+    Merge,
 }
 
 impl Default for OverwritePolicy {
@@ -292,4 +1083,30 @@ impl Default for OverwritePolicy {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    #[cfg(unix)]
+    #[test]
+    fn test_file_mode() -> anyhow::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        use camino::Utf8PathBuf;
+
+        use super::file_mode;
+
+        let temp = tempfile::tempdir()?;
+        let source = Utf8PathBuf::from_path_buf(temp.path().join("source")).unwrap();
+        std::fs::write(&source, "source")?;
+
+        std::fs::set_permissions(&source, std::fs::Permissions::from_mode(0o644))?;
+        assert_eq!(file_mode(&source, None)?, None);
+        assert_eq!(file_mode(&source, Some(0o600))?, Some(0o600));
+
+        std::fs::set_permissions(&source, std::fs::Permissions::from_mode(0o555))?;
+        assert_eq!(file_mode(&source, None)?, Some(0o755));
+
+        std::fs::set_permissions(&source, std::fs::Permissions::from_mode(0o4711))?;
+        assert_eq!(file_mode(&source, None)?, Some(0o711));
+
+        Ok(())
+    }
+}
@@ -4,11 +4,16 @@ use camino::Utf8PathBuf;
 use serde::{Deserialize, Serialize};
 use linked_hash_map::LinkedHashMap;
 
+pub use crate::archetype::archetype_manifest::git::GitConfig;
+pub use crate::archetype::archetype_manifest::post_render::PostRenderHook;
 pub use crate::archetype::archetype_manifest::requirements::RuntimeRequirements;
 use crate::archetype::archetype_manifest::scripting::ScriptingConfig;
 use crate::archetype::archetype_manifest::templating::TemplatingConfig;
+pub use crate::archetype::archetype_manifest::templating::{LineEndings, SymlinkBehavior, TemplatingRule};
 use crate::errors::ArchetypeError;
 
+mod git;
+mod post_render;
 mod requirements;
 mod scripting;
 mod templating;
@@ -31,6 +36,10 @@ pub struct ArchetypeManifest {
     scripting: ScriptingConfig,
     #[serde(default = "TemplatingConfig::default")]
     templating: TemplatingConfig,
+    #[serde(skip_serializing_if = "Vec::is_empty", default, alias = "on_render")]
+    post_render: Vec<PostRenderHook>,
+    #[serde(default = "GitConfig::default")]
+    git: GitConfig,
 }
 
 impl ArchetypeManifest {
@@ -146,6 +155,15 @@ impl ArchetypeManifest {
     pub fn templating(&self) -> &TemplatingConfig {
         &self.templating
     }
+
+    /// Commands to run in the destination once the archetype has been rendered.
+    pub fn post_render(&self) -> &[PostRenderHook] {
+        &self.post_render
+    }
+
+    pub fn git(&self) -> &GitConfig {
+        &self.git
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_COMMIT_MESSAGE: &str = "Initial commit";
+
+/// Whether a git repository is initialized in the destination once an archetype has been rendered, with the rendered
+/// files committed using `message`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GitConfig {
+    #[serde(default)]
+    init: bool,
+    #[serde(default = "default_message")]
+    message: String,
+}
+
+impl GitConfig {
+    pub fn init(&self) -> bool {
+        self.init
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl Default for GitConfig {
+    fn default() -> Self {
+        GitConfig {
+            init: false,
+            message: default_message(),
+        }
+    }
+}
+
+fn default_message() -> String {
+    DEFAULT_COMMIT_MESSAGE.to_owned()
+}
@@ -0,0 +1,172 @@
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::ArchetypeError;
+
+/// A command run in the destination once an archetype has been rendered, such as a formatter.  It may be declared
+/// as just the command line, or as a map to also set the directory it runs in, relative to the destination, and a
+/// switch that must be enabled for it to run.  A failing command is reported as a warning unless it is `required`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(from = "PostRenderHookDefinition")]
+pub struct PostRenderHook {
+    command: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    directory: Option<Utf8PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    if_switch: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    required: bool,
+}
+
+impl PostRenderHook {
+    pub fn new<C: Into<String>>(command: C) -> PostRenderHook {
+        PostRenderHook {
+            command: command.into(),
+            directory: None,
+            if_switch: None,
+            required: false,
+        }
+    }
+
+    pub fn with_directory<D: Into<Utf8PathBuf>>(mut self, directory: D) -> PostRenderHook {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    pub fn with_if_switch<S: Into<String>>(mut self, switch: S) -> PostRenderHook {
+        self.if_switch = Some(switch.into());
+        self
+    }
+
+    pub fn with_required(mut self, required: bool) -> PostRenderHook {
+        self.required = required;
+        self
+    }
+
+    pub fn command(&self) -> &str {
+        self.command.as_str()
+    }
+
+    /// The program and arguments of the command, split as a shell would, or `None` if it is empty or its quoting
+    /// is unbalanced.
+    pub fn command_line(&self) -> Option<Vec<String>> {
+        shlex::split(&self.command).filter(|args| !args.is_empty())
+    }
+
+    pub fn directory(&self) -> Option<&Utf8Path> {
+        self.directory.as_deref()
+    }
+
+    /// Ensures that the directory the command runs in, if set, is a path within the destination.
+    pub fn check_directory(&self) -> Result<(), ArchetypeError> {
+        let Some(directory) = self.directory() else {
+            return Ok(());
+        };
+        let within = !directory.as_str().starts_with('~')
+            && directory
+                .components()
+                .all(|component| matches!(component, Utf8Component::Normal(_) | Utf8Component::CurDir));
+        if within {
+            Ok(())
+        } else {
+            Err(ArchetypeError::PostRenderHookDirectoryError {
+                command: self.command.clone(),
+                directory: directory.to_path_buf(),
+            })
+        }
+    }
+
+    pub fn if_switch(&self) -> Option<&str> {
+        self.if_switch.as_deref()
+    }
+
+    pub fn required(&self) -> bool {
+        self.required
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PostRenderHookDefinition {
+    Command(String),
+    Hook {
+        command: String,
+        #[serde(default)]
+        directory: Option<Utf8PathBuf>,
+        #[serde(default)]
+        if_switch: Option<String>,
+        #[serde(default)]
+        required: bool,
+    },
+}
+
+impl From<PostRenderHookDefinition> for PostRenderHook {
+    fn from(definition: PostRenderHookDefinition) -> Self {
+        match definition {
+            PostRenderHookDefinition::Command(command) => PostRenderHook::new(command),
+            PostRenderHookDefinition::Hook {
+                command,
+                directory,
+                if_switch,
+                required,
+            } => PostRenderHook {
+                command,
+                directory,
+                if_switch,
+                required,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_definitions() {
+        let hooks: Vec<PostRenderHook> = serde_yaml::from_str(
+            r#"
+            - cargo fmt
+            - command: "prettier --write 'src/**/*.ts'"
+              directory: web
+              if_switch: web
+              required: true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            hooks[0].command_line(),
+            Some(vec!["cargo".to_owned(), "fmt".to_owned()])
+        );
+        assert_eq!(hooks[0].directory(), None);
+        assert!(!hooks[0].required());
+        assert_eq!(
+            hooks[1].command_line(),
+            Some(vec![
+                "prettier".to_owned(),
+                "--write".to_owned(),
+                "src/**/*.ts".to_owned()
+            ])
+        );
+        assert_eq!(hooks[1].directory(), Some(Utf8Path::new("web")));
+        assert_eq!(hooks[1].if_switch(), Some("web"));
+        assert!(hooks[1].required());
+        assert_eq!(PostRenderHook::new("  ").command_line(), None);
+    }
+
+    #[test]
+    fn test_hook_directories() {
+        assert!(PostRenderHook::new("cargo fmt").check_directory().is_ok());
+        assert!(PostRenderHook::new("npm ci").with_directory("./web/app").check_directory().is_ok());
+        for directory in ["/", "/tmp", "..", "web/../..", "~/web"] {
+            let hook = PostRenderHook::new("npm ci").with_directory(directory);
+            assert!(
+                matches!(hook.check_directory(), Err(ArchetypeError::PostRenderHookDirectoryError { .. })),
+                "{} was allowed",
+                directory
+            );
+        }
+    }
+}
@@ -1,7 +1,11 @@
+use std::borrow::Cow;
+
 use camino::{Utf8Path, Utf8PathBuf};
 use serde::{Deserialize, Serialize};
 use archetect_templating::UndefinedBehavior as MinijinjaUndefinedBehavior;
 
+use crate::archetype::archetype::RuleAction;
+
 const DEFAULT_CONTENT_DIRECTORY: &str = ".";
 const DEFAULT_TEMPLATES_DIRECTORY: &str = "templates";
 
@@ -13,6 +17,24 @@ pub struct TemplatingConfig {
     templates: Utf8PathBuf,
     #[serde(default = "default_undefined_behavior")]
     undefined_behavior: UndefinedBehavior,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    rules: Vec<TemplatingRule>,
+    #[serde(default)]
+    symlinks: SymlinkBehavior,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    template_suffixes: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    ignore: Vec<String>,
+    #[serde(default)]
+    line_endings: LineEndings,
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    trailing_newline: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    binary: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    text: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    max_render_size: Option<u64>,
 }
 
 impl TemplatingConfig {
@@ -26,6 +48,54 @@ impl TemplatingConfig {
     pub fn undefined_behavior(&self) -> UndefinedBehavior {
         self.undefined_behavior
     }
+
+    pub fn rules(&self) -> &[TemplatingRule] {
+        &self.rules
+    }
+
+    pub fn symlinks(&self) -> SymlinkBehavior {
+        self.symlinks
+    }
+
+    /// Suffixes, such as `.jinja` or `.j2`, marking the only files to be rendered, which are written without the
+    /// suffix.  All other files are copied as they are.  When empty, every file that is not binary is rendered.
+    pub fn template_suffixes(&self) -> &[String] {
+        &self.template_suffixes
+    }
+
+    /// Gitignore-style patterns, relative to each directory rendered, for paths to leave out of renders.  These apply
+    /// alongside any `.archetectignore` files within the content directory.
+    pub fn ignore(&self) -> &[String] {
+        &self.ignore
+    }
+
+    /// The line endings of rendered files, unless overridden by a templating rule.
+    pub fn line_endings(&self) -> LineEndings {
+        self.line_endings
+    }
+
+    /// Whether rendered files are ensured to end with a newline, unless overridden by a templating rule.
+    pub fn trailing_newline(&self) -> bool {
+        self.trailing_newline
+    }
+
+    /// Glob patterns, relative to each directory rendered, for files that are always copied as binary, rather than
+    /// having their contents inspected.  Templating rules and template suffixes take precedence.
+    pub fn binary(&self) -> &[String] {
+        &self.binary
+    }
+
+    /// Glob patterns, relative to each directory rendered, for files that are always rendered as text, rather than
+    /// having their contents inspected.  Binary patterns, templating rules, and template suffixes take precedence.
+    pub fn text(&self) -> &[String] {
+        &self.text
+    }
+
+    /// The size in bytes beyond which files are copied with a warning rather than rendered, unless a templating rule,
+    /// template suffix, or text pattern calls for them to be rendered.  Files of any size are rendered when unset.
+    pub fn max_render_size(&self) -> Option<u64> {
+        self.max_render_size
+    }
 }
 
 impl Default for TemplatingConfig {
@@ -34,6 +104,15 @@ impl Default for TemplatingConfig {
             content: default_content_directory(),
             templates: default_templates_directory(),
             undefined_behavior: default_undefined_behavior(),
+            rules: Vec::new(),
+            symlinks: Default::default(),
+            template_suffixes: Vec::new(),
+            ignore: Vec::new(),
+            line_endings: Default::default(),
+            trailing_newline: false,
+            binary: Vec::new(),
+            text: Vec::new(),
+            max_render_size: None,
         }
     }
 }
@@ -50,6 +129,144 @@ fn default_undefined_behavior() -> UndefinedBehavior {
     UndefinedBehavior::Strict
 }
 
+/// Decides how files matching any of a set of glob patterns are treated when a directory is rendered.  Patterns are
+/// matched against paths relative to the directory being rendered.  When `if_switch` is set, the rule only applies
+/// if that switch is enabled, and files matching its action are skipped otherwise.  A rule's `mode` is an octal
+/// Unix permission mode, such as "0755", applied to matching files in place of the source file's mode, while
+/// `line_endings` and `trailing_newline` override the templating settings of the same name for matching files.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TemplatingRule {
+    patterns: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    action: Option<RuleAction>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    line_endings: Option<LineEndings>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    trailing_newline: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    if_switch: Option<String>,
+}
+
+impl TemplatingRule {
+    pub fn new<P: Into<String>>(patterns: Vec<P>) -> TemplatingRule {
+        TemplatingRule {
+            patterns: patterns.into_iter().map(Into::into).collect(),
+            action: None,
+            mode: None,
+            line_endings: None,
+            trailing_newline: None,
+            if_switch: None,
+        }
+    }
+
+    pub fn with_action(mut self, action: RuleAction) -> TemplatingRule {
+        self.action = Some(action);
+        self
+    }
+
+    pub fn with_mode<M: Into<String>>(mut self, mode: M) -> TemplatingRule {
+        self.mode = Some(mode.into());
+        self
+    }
+
+    pub fn with_line_endings(mut self, line_endings: LineEndings) -> TemplatingRule {
+        self.line_endings = Some(line_endings);
+        self
+    }
+
+    pub fn with_trailing_newline(mut self, trailing_newline: bool) -> TemplatingRule {
+        self.trailing_newline = Some(trailing_newline);
+        self
+    }
+
+    pub fn with_if_switch<S: Into<String>>(mut self, switch: S) -> TemplatingRule {
+        self.if_switch = Some(switch.into());
+        self
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn action(&self) -> Option<RuleAction> {
+        self.action
+    }
+
+    pub fn mode(&self) -> Option<&str> {
+        self.mode.as_deref()
+    }
+
+    pub fn line_endings(&self) -> Option<LineEndings> {
+        self.line_endings
+    }
+
+    pub fn trailing_newline(&self) -> Option<bool> {
+        self.trailing_newline
+    }
+
+    pub fn if_switch(&self) -> Option<&str> {
+        self.if_switch.as_deref()
+    }
+}
+
+/// How line endings in rendered files are written.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEndings {
+    /// Leave line endings as they were rendered
+    #[default]
+    Preserve,
+    Lf,
+    Crlf,
+    /// The line endings of the platform rendering the archetype
+    Native,
+}
+
+impl LineEndings {
+    /// Rewrites every line ending in `contents`, and ensures it ends with a line ending if `trailing_newline` is set.
+    pub fn normalize<'a>(&self, contents: &'a str, trailing_newline: bool) -> Cow<'a, str> {
+        let mut contents = match self.resolve() {
+            LineEndings::Lf if contents.contains("\r\n") => Cow::Owned(contents.replace("\r\n", "\n")),
+            LineEndings::Crlf if contents.contains('\n') => {
+                Cow::Owned(contents.replace("\r\n", "\n").replace('\n', "\r\n"))
+            }
+            _ => Cow::Borrowed(contents),
+        };
+        if trailing_newline && !contents.is_empty() && !contents.ends_with('\n') {
+            let newline = match self.resolve() {
+                LineEndings::Crlf => "\r\n",
+                LineEndings::Preserve if contents.contains("\r\n") => "\r\n",
+                _ => "\n",
+            };
+            contents.to_mut().push_str(newline);
+        }
+        contents
+    }
+
+    fn resolve(&self) -> LineEndings {
+        match self {
+            LineEndings::Native if cfg!(windows) => LineEndings::Crlf,
+            LineEndings::Native => LineEndings::Lf,
+            line_endings => *line_endings,
+        }
+    }
+}
+
+/// How symbolic links within a content directory are rendered.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymlinkBehavior {
+    /// Recreate links in the destination with their targets unchanged
+    #[default]
+    Preserve,
+    /// Recreate links in the destination with their targets rendered as templates
+    Render,
+    /// Render whatever links point to as if it were in the content directory itself
+    Dereference,
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum UndefinedBehavior {
     Lenient,
@@ -66,3 +283,32 @@ impl UndefinedBehavior {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_endings() {
+        let mixed = "one\r\ntwo\nthree";
+        assert_eq!(LineEndings::Lf.normalize(mixed, false), "one\ntwo\nthree");
+        assert_eq!(LineEndings::Crlf.normalize(mixed, false), "one\r\ntwo\r\nthree");
+        assert_eq!(LineEndings::Preserve.normalize(mixed, false), mixed);
+    }
+
+    #[test]
+    fn test_trailing_newline() {
+        assert_eq!(LineEndings::Lf.normalize("one\ntwo", true), "one\ntwo\n");
+        assert_eq!(LineEndings::Crlf.normalize("one\ntwo", true), "one\r\ntwo\r\n");
+        assert_eq!(LineEndings::Preserve.normalize("one\r\ntwo", true), "one\r\ntwo\r\n");
+        assert_eq!(LineEndings::Lf.normalize("one\n", true), "one\n");
+        assert_eq!(LineEndings::Lf.normalize("", true), "");
+    }
+
+    #[test]
+    fn test_max_render_size() {
+        assert_eq!(TemplatingConfig::default().max_render_size(), None);
+        let templating: TemplatingConfig = serde_yaml::from_str("max_render_size: 1048576").unwrap();
+        assert_eq!(templating.max_render_size(), Some(1048576));
+    }
+}
@@ -0,0 +1,94 @@
+use camino::Utf8Path;
+use git2::{IndexAddOption, Repository, Signature};
+
+use archetect_api::CommandRequest;
+
+use crate::archetype::pristine::PRISTINE_DIRECTORY;
+use crate::archetype::render_context::RenderContext;
+use crate::archetype::render_record::RENDER_RECORD_DIRECTORY;
+use crate::errors::ArchetypeError;
+use crate::Archetect;
+
+/// Initializes a git repository in `destination`, stages everything rendered into it that is not ignored, other than
+/// the pristine copies kept beside the render record, and commits it with `message`.  The commit is
+/// authored with the `author_name` and `author_email` answers, falling back to the git configuration, and is skipped
+/// with a warning if neither is available.  Nothing is done in dry runs, when output is not being written to the
+/// local filesystem, or when `destination` is already within a repository.
+pub fn initialize_repository(
+    archetect: &Archetect,
+    render_context: &RenderContext,
+    destination: &Utf8Path,
+    message: &str,
+) -> Result<(), ArchetypeError> {
+    if render_context.dry_run() {
+        archetect.request(CommandRequest::LogInfo(format!(
+            "Dry Run: skipping initialization of a git repository in '{}'",
+            destination
+        )));
+        return Ok(());
+    }
+
+    if !render_context.sink().is_filesystem() {
+        archetect.request(CommandRequest::LogInfo(
+            "Skipping git repository initialization: output is not being written to the filesystem".to_owned(),
+        ));
+        return Ok(());
+    }
+
+    if let Ok(repository) = Repository::discover(destination) {
+        let location = repository.workdir().unwrap_or(repository.path()).display().to_string();
+        archetect.request(CommandRequest::LogInfo(format!(
+            "Skipping git repository initialization: '{}' is already within the repository at '{}'",
+            destination, location
+        )));
+        return Ok(());
+    }
+
+    let error = |source: git2::Error| ArchetypeError::GitInitError {
+        path: destination.to_path_buf(),
+        source,
+    };
+
+    let repository = Repository::init(destination).map_err(error)?;
+    let mut index = repository.index().map_err(error)?;
+    let pristine = Utf8Path::new(RENDER_RECORD_DIRECTORY).join(PRISTINE_DIRECTORY);
+    let mut skip_pristine = |path: &std::path::Path, _: &[u8]| i32::from(path.starts_with(&pristine));
+    index
+        .add_all(["*"].iter(), IndexAddOption::DEFAULT, Some(&mut skip_pristine))
+        .map_err(error)?;
+    index.write().map_err(error)?;
+    let tree_id = index.write_tree().map_err(error)?;
+    let tree = repository.find_tree(tree_id).map_err(error)?;
+
+    let Some(signature) = signature(render_context, &repository) else {
+        archetect.request(CommandRequest::LogWarn(format!(
+            "Initialized a git repository in '{}', but skipped the initial commit: no `author_name` and `author_email` \
+             answers or git user are configured",
+            destination
+        )));
+        return Ok(());
+    };
+    repository
+        .commit(Some("HEAD"), &signature, &signature, message, &tree, &[])
+        .map_err(error)?;
+
+    archetect.request(CommandRequest::LogInfo(format!(
+        "Initialized a git repository in '{}'",
+        destination
+    )));
+    Ok(())
+}
+
+fn signature(render_context: &RenderContext, repository: &Repository) -> Option<Signature<'static>> {
+    let answer = |key: &str| {
+        render_context
+            .answers()
+            .get(key)
+            .and_then(|value| value.clone().into_string().ok())
+            .filter(|value| !value.trim().is_empty())
+    };
+    match (answer("author_name"), answer("author_email")) {
+        (Some(name), Some(email)) => Signature::now(&name, &email).ok(),
+        _ => repository.signature().ok().map(|signature| signature.to_owned()),
+    }
+}
@@ -1,4 +1,14 @@
 pub mod archetype;
 pub mod archetype_directory;
 pub mod archetype_manifest;
+pub mod git_init;
+pub mod output_sink;
+pub mod post_render;
+pub mod pristine;
 pub mod render_context;
+pub mod render_plan;
+pub mod render_record;
+pub mod render_report;
+pub mod render_rules;
+pub mod render_transaction;
+pub mod structured_merge;
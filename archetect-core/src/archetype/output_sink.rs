@@ -0,0 +1,307 @@
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::fs;
+use std::io::ErrorKind;
+use std::sync::{Arc, Mutex};
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+use crate::archetype::archetype::create_symlink;
+use crate::errors::RenderError;
+
+pub mod archive;
+
+pub use archive::{ArchiveFormat, ArchiveSink};
+
+/// Where the directories, files, and links produced by a render are written.  Paths are those of the render's
+/// destination, so that a sink not backed by the filesystem can interpret them relative to it.
+pub trait OutputSink: Debug + Send + Sync + 'static {
+    fn create_dir_all(&self, path: &Utf8Path) -> Result<(), RenderError>;
+
+    fn write(&self, path: &Utf8Path, contents: &[u8]) -> Result<(), RenderError>;
+
+    /// Writes the contents of the local file at `source` to `destination`.
+    fn copy(&self, source: &Utf8Path, destination: &Utf8Path) -> Result<(), RenderError> {
+        let contents = fs::read(source).map_err(|err| RenderError::FileReadError {
+            path: source.to_path_buf(),
+            source: err,
+        })?;
+        self.write(destination, &contents)
+    }
+
+    /// Creates a symbolic link at `link` pointing to `target`, replacing any link already there.
+    fn symlink(&self, target: &Utf8Path, link: &Utf8Path) -> Result<(), RenderError>;
+
+    fn set_mode(&self, path: &Utf8Path, mode: u32) -> Result<(), RenderError>;
+
+    fn read(&self, path: &Utf8Path) -> Result<Vec<u8>, RenderError>;
+
+    fn remove(&self, path: &Utf8Path) -> Result<(), RenderError>;
+
+    fn exists(&self, path: &Utf8Path) -> bool;
+
+    fn is_file(&self, path: &Utf8Path) -> bool;
+
+    fn is_dir(&self, path: &Utf8Path) -> bool;
+
+    /// Whether output lands on the local filesystem, where it can be staged by a transaction and acted on by
+    /// post-render hooks.
+    fn is_filesystem(&self) -> bool {
+        false
+    }
+
+    /// Completes the output once everything has been rendered, such as by writing out an archive.
+    fn finish(&self) -> Result<(), RenderError> {
+        Ok(())
+    }
+}
+
+/// Writes output straight to the local filesystem, which is the default.
+#[derive(Clone, Debug, Default)]
+pub struct FilesystemSink;
+
+impl OutputSink for FilesystemSink {
+    fn create_dir_all(&self, path: &Utf8Path) -> Result<(), RenderError> {
+        fs::create_dir_all(path).map_err(|err| RenderError::CreateDirectoryError {
+            path: path.to_path_buf(),
+            source: err,
+        })
+    }
+
+    fn write(&self, path: &Utf8Path, contents: &[u8]) -> Result<(), RenderError> {
+        fs::write(path, contents).map_err(|err| RenderError::WriteError {
+            path: path.to_path_buf(),
+            source: err,
+        })
+    }
+
+    fn copy(&self, source: &Utf8Path, destination: &Utf8Path) -> Result<(), RenderError> {
+        fs::copy(source, destination).map_err(|err| RenderError::CopyError {
+            from: source.to_path_buf(),
+            to: destination.to_path_buf(),
+            source: err,
+        })?;
+        Ok(())
+    }
+
+    fn symlink(&self, target: &Utf8Path, link: &Utf8Path) -> Result<(), RenderError> {
+        if link.symlink_metadata().is_ok() {
+            fs::remove_file(link).map_err(|err| RenderError::RemoveError {
+                path: link.to_path_buf(),
+                source: err,
+            })?;
+        }
+        create_symlink(target, link)
+    }
+
+    #[cfg(unix)]
+    fn set_mode(&self, path: &Utf8Path, mode: u32) -> Result<(), RenderError> {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).map_err(|err| RenderError::PermissionsError {
+            path: path.to_path_buf(),
+            source: err,
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn set_mode(&self, _path: &Utf8Path, _mode: u32) -> Result<(), RenderError> {
+        Ok(())
+    }
+
+    fn read(&self, path: &Utf8Path) -> Result<Vec<u8>, RenderError> {
+        fs::read(path).map_err(|err| RenderError::FileReadError {
+            path: path.to_path_buf(),
+            source: err,
+        })
+    }
+
+    fn remove(&self, path: &Utf8Path) -> Result<(), RenderError> {
+        let result = if path.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        };
+        result.map_err(|err| RenderError::RemoveError {
+            path: path.to_path_buf(),
+            source: err,
+        })
+    }
+
+    fn exists(&self, path: &Utf8Path) -> bool {
+        path.exists()
+    }
+
+    fn is_file(&self, path: &Utf8Path) -> bool {
+        path.is_file()
+    }
+
+    fn is_dir(&self, path: &Utf8Path) -> bool {
+        path.is_dir()
+    }
+
+    fn is_filesystem(&self) -> bool {
+        true
+    }
+}
+
+/// An entry written to a [MemorySink].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MemoryEntry {
+    Directory,
+    File { contents: Vec<u8>, mode: Option<u32> },
+    Link { target: Utf8PathBuf },
+}
+
+/// Captures output in memory, for embedding applications and tests to inspect a render without touching the local
+/// filesystem.  Clones share the same entries.
+#[derive(Clone, Debug, Default)]
+pub struct MemorySink {
+    entries: Arc<Mutex<BTreeMap<Utf8PathBuf, MemoryEntry>>>,
+}
+
+impl MemorySink {
+    pub fn new() -> MemorySink {
+        Default::default()
+    }
+
+    /// Every directory, file, and link written, in path order.
+    pub fn entries(&self) -> BTreeMap<Utf8PathBuf, MemoryEntry> {
+        self.entries.lock().expect("Lock Error").clone()
+    }
+
+    /// The contents of every file written, in path order.
+    pub fn files(&self) -> BTreeMap<Utf8PathBuf, Vec<u8>> {
+        self.entries
+            .lock()
+            .expect("Lock Error")
+            .iter()
+            .filter_map(|(path, entry)| match entry {
+                MemoryEntry::File { contents, .. } => Some((path.clone(), contents.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The contents of the file written to `path`, if any.
+    pub fn contents<P: AsRef<Utf8Path>>(&self, path: P) -> Option<Vec<u8>> {
+        match self.entries.lock().expect("Lock Error").get(path.as_ref()) {
+            Some(MemoryEntry::File { contents, .. }) => Some(contents.clone()),
+            _ => None,
+        }
+    }
+
+    fn insert(&self, path: &Utf8Path, entry: MemoryEntry) {
+        let mut entries = self.entries.lock().expect("Lock Error");
+        for ancestor in path
+            .ancestors()
+            .skip(1)
+            .filter(|ancestor| !ancestor.as_str().is_empty())
+        {
+            entries.entry(ancestor.to_path_buf()).or_insert(MemoryEntry::Directory);
+        }
+        entries.insert(path.to_path_buf(), entry);
+    }
+}
+
+impl OutputSink for MemorySink {
+    fn create_dir_all(&self, path: &Utf8Path) -> Result<(), RenderError> {
+        if !self.is_dir(path) {
+            self.insert(path, MemoryEntry::Directory);
+        }
+        Ok(())
+    }
+
+    fn write(&self, path: &Utf8Path, contents: &[u8]) -> Result<(), RenderError> {
+        let mode = match self.entries.lock().expect("Lock Error").get(path) {
+            Some(MemoryEntry::File { mode, .. }) => *mode,
+            _ => None,
+        };
+        self.insert(
+            path,
+            MemoryEntry::File {
+                contents: contents.to_vec(),
+                mode,
+            },
+        );
+        Ok(())
+    }
+
+    fn symlink(&self, target: &Utf8Path, link: &Utf8Path) -> Result<(), RenderError> {
+        self.insert(
+            link,
+            MemoryEntry::Link {
+                target: target.to_path_buf(),
+            },
+        );
+        Ok(())
+    }
+
+    fn set_mode(&self, path: &Utf8Path, mode: u32) -> Result<(), RenderError> {
+        if let Some(MemoryEntry::File { mode: existing, .. }) = self.entries.lock().expect("Lock Error").get_mut(path) {
+            *existing = Some(mode);
+        }
+        Ok(())
+    }
+
+    fn read(&self, path: &Utf8Path) -> Result<Vec<u8>, RenderError> {
+        self.contents(path).ok_or_else(|| RenderError::FileReadError {
+            path: path.to_path_buf(),
+            source: ErrorKind::NotFound.into(),
+        })
+    }
+
+    fn remove(&self, path: &Utf8Path) -> Result<(), RenderError> {
+        self.entries
+            .lock()
+            .expect("Lock Error")
+            .retain(|entry, _| !entry.starts_with(path));
+        Ok(())
+    }
+
+    fn exists(&self, path: &Utf8Path) -> bool {
+        self.entries.lock().expect("Lock Error").contains_key(path)
+    }
+
+    fn is_file(&self, path: &Utf8Path) -> bool {
+        matches!(
+            self.entries.lock().expect("Lock Error").get(path),
+            Some(MemoryEntry::File { .. })
+        )
+    }
+
+    fn is_dir(&self, path: &Utf8Path) -> bool {
+        matches!(
+            self.entries.lock().expect("Lock Error").get(path),
+            Some(MemoryEntry::Directory)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_sink() {
+        let sink = MemorySink::new();
+        sink.write(Utf8Path::new("out/src/main.rs"), b"fn main() {}").unwrap();
+        sink.set_mode(Utf8Path::new("out/src/main.rs"), 0o644).unwrap();
+        sink.write(Utf8Path::new("out/src/main.rs"), b"fn main() {}\n").unwrap();
+
+        assert!(sink.is_dir(Utf8Path::new("out/src")));
+        assert!(sink.is_file(Utf8Path::new("out/src/main.rs")));
+        assert_eq!(
+            sink.entries().get(Utf8Path::new("out/src/main.rs")),
+            Some(&MemoryEntry::File {
+                contents: b"fn main() {}\n".to_vec(),
+                mode: Some(0o644)
+            })
+        );
+
+        sink.remove(Utf8Path::new("out/src")).unwrap();
+        assert!(!sink.exists(Utf8Path::new("out/src/main.rs")));
+        assert!(sink.read(Utf8Path::new("out/src/main.rs")).is_err());
+        assert!(sink.is_dir(Utf8Path::new("out")));
+    }
+}
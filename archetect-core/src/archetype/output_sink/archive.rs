@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Seek, Write};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use tar::EntryType;
+use zip::result::ZipResult;
+use zip::write::SimpleFileOptions;
+use zip::{DateTime, ZipWriter};
+
+use crate::archetype::output_sink::{MemoryEntry, MemorySink, OutputSink};
+use crate::errors::RenderError;
+
+const DIRECTORY_MODE: u32 = 0o755;
+const FILE_MODE: u32 = 0o644;
+const LINK_MODE: u32 = 0o777;
+
+/// The formats an [ArchiveSink] can write.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// The format implied by the name of `path`, if it ends in `.tar`, `.tar.gz`, `.tgz`, or `.zip`.
+    pub fn from_path<P: AsRef<Utf8Path>>(path: P) -> Option<ArchiveFormat> {
+        let name = path.as_ref().file_name()?.to_ascii_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Collects output in memory, and writes it to an archive at `path` when finished, with entries named relative to
+/// `root`, which is normally the render's destination.  Clones share the same entries.
+#[derive(Clone, Debug)]
+pub struct ArchiveSink {
+    path: Utf8PathBuf,
+    root: Utf8PathBuf,
+    format: ArchiveFormat,
+    memory: MemorySink,
+}
+
+impl ArchiveSink {
+    pub fn new<P: Into<Utf8PathBuf>, R: Into<Utf8PathBuf>>(path: P, root: R, format: ArchiveFormat) -> ArchiveSink {
+        ArchiveSink {
+            path: path.into(),
+            root: root.into(),
+            format,
+            memory: MemorySink::new(),
+        }
+    }
+
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+
+    pub fn format(&self) -> ArchiveFormat {
+        self.format
+    }
+
+    /// The archive's entries, named relative to the root, in path order.  Entries outside of the root are left out.
+    fn archive_entries(&self) -> BTreeMap<String, MemoryEntry> {
+        self.memory
+            .entries()
+            .into_iter()
+            .filter_map(|(path, entry)| {
+                let relative = path.strip_prefix(&self.root).ok()?;
+                (!relative.as_str().is_empty()).then(|| (relative.as_str().replace('\\', "/"), entry))
+            })
+            .collect()
+    }
+
+    fn error<M: Into<String>>(&self, message: M) -> RenderError {
+        RenderError::ArchiveError {
+            path: self.path.clone(),
+            message: message.into(),
+        }
+    }
+}
+
+impl OutputSink for ArchiveSink {
+    fn create_dir_all(&self, path: &Utf8Path) -> Result<(), RenderError> {
+        self.memory.create_dir_all(path)
+    }
+
+    fn write(&self, path: &Utf8Path, contents: &[u8]) -> Result<(), RenderError> {
+        self.memory.write(path, contents)
+    }
+
+    fn symlink(&self, target: &Utf8Path, link: &Utf8Path) -> Result<(), RenderError> {
+        self.memory.symlink(target, link)
+    }
+
+    fn set_mode(&self, path: &Utf8Path, mode: u32) -> Result<(), RenderError> {
+        self.memory.set_mode(path, mode)
+    }
+
+    fn read(&self, path: &Utf8Path) -> Result<Vec<u8>, RenderError> {
+        self.memory.read(path)
+    }
+
+    fn remove(&self, path: &Utf8Path) -> Result<(), RenderError> {
+        self.memory.remove(path)
+    }
+
+    fn exists(&self, path: &Utf8Path) -> bool {
+        self.memory.exists(path)
+    }
+
+    fn is_file(&self, path: &Utf8Path) -> bool {
+        self.memory.is_file(path)
+    }
+
+    fn is_dir(&self, path: &Utf8Path) -> bool {
+        self.memory.is_dir(path)
+    }
+
+    fn finish(&self) -> Result<(), RenderError> {
+        let entries = self.archive_entries();
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|err| RenderError::CreateDirectoryError {
+                path: parent.to_path_buf(),
+                source: err,
+            })?;
+        }
+        let file = File::create(&self.path).map_err(|err| RenderError::CreateFileError {
+            path: self.path.clone(),
+            source: err,
+        })?;
+        let writer = BufWriter::new(file);
+        let written = match self.format {
+            ArchiveFormat::Tar => write_tar(writer, &entries).map_err(|err| self.error(err.to_string())),
+            ArchiveFormat::TarGz => write_tar(GzEncoder::new(writer, Compression::default()), &entries)
+                .and_then(GzEncoder::finish)
+                .map_err(|err| self.error(err.to_string())),
+            ArchiveFormat::Zip => write_zip(writer, &entries).map_err(|err| self.error(err.to_string())),
+        }?;
+        written.into_inner().map(drop).map_err(|err| RenderError::WriteError {
+            path: self.path.clone(),
+            source: err.into_error(),
+        })
+    }
+}
+
+/// Writes `entries` to a tar archive in `writer`.  Entries are owned by root and dated at the epoch, so that
+/// archives are reproducible.
+fn write_tar<W: Write>(writer: W, entries: &BTreeMap<String, MemoryEntry>) -> io::Result<W> {
+    let mut archive = tar::Builder::new(writer);
+    for (name, entry) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_mtime(0);
+        match entry {
+            MemoryEntry::Directory => {
+                header.set_entry_type(EntryType::Directory);
+                header.set_mode(DIRECTORY_MODE);
+                header.set_size(0);
+                archive.append_data(&mut header, format!("{}/", name), io::empty())?;
+            }
+            MemoryEntry::File { contents, mode } => {
+                header.set_entry_type(EntryType::Regular);
+                header.set_mode(mode.unwrap_or(FILE_MODE) & 0o7777);
+                header.set_size(contents.len() as u64);
+                archive.append_data(&mut header, name, contents.as_slice())?;
+            }
+            MemoryEntry::Link { target } => {
+                header.set_entry_type(EntryType::Symlink);
+                header.set_mode(LINK_MODE);
+                header.set_size(0);
+                archive.append_link(&mut header, name, target)?;
+            }
+        }
+    }
+    archive.into_inner()
+}
+
+/// Writes `entries` to a zip archive in `writer`.  Entries are dated 1980-01-01, the earliest date a zip archive can
+/// hold, so that archives are reproducible.
+fn write_zip<W: Write + Seek>(writer: W, entries: &BTreeMap<String, MemoryEntry>) -> ZipResult<W> {
+    let mut archive = ZipWriter::new(writer);
+    let options = SimpleFileOptions::default().last_modified_time(DateTime::default());
+    for (name, entry) in entries {
+        match entry {
+            MemoryEntry::Directory => archive.add_directory(name, options.unix_permissions(DIRECTORY_MODE))?,
+            MemoryEntry::File { contents, mode } => {
+                let options = options
+                    .unix_permissions(mode.unwrap_or(FILE_MODE) & 0o7777)
+                    .large_file(contents.len() as u64 >= u32::MAX as u64);
+                archive.start_file(name, options)?;
+                archive.write_all(contents)?;
+            }
+            MemoryEntry::Link { target } => {
+                archive.add_symlink(name, target.as_str(), options.unix_permissions(LINK_MODE))?
+            }
+        }
+    }
+    archive.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_format() {
+        assert_eq!(
+            ArchiveFormat::from_path("out/project.tar.gz"),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(ArchiveFormat::from_path("project.TGZ"), Some(ArchiveFormat::TarGz));
+        assert_eq!(ArchiveFormat::from_path("project.tar"), Some(ArchiveFormat::Tar));
+        assert_eq!(ArchiveFormat::from_path("project.zip"), Some(ArchiveFormat::Zip));
+        assert_eq!(ArchiveFormat::from_path("project"), None);
+    }
+}
@@ -0,0 +1,181 @@
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+use archetect_api::{BoolPromptInfo, CommandRequest, CommandResponse};
+
+use crate::archetype::archetype_manifest::PostRenderHook;
+use crate::archetype::render_context::RenderContext;
+use crate::errors::ArchetypeError;
+use crate::Archetect;
+
+/// The post-render hooks of an archetype, along with the destination it was rendered into.
+pub type DeferredHooks = (Utf8PathBuf, Vec<PostRenderHook>);
+
+/// Post-render hooks waiting on an in-progress transaction to commit before they can run against the files they
+/// are meant to act on.  Clones share the same underlying queue, so that hooks deferred by components are run by
+/// the render that owns the transaction.
+#[derive(Clone, Debug, Default)]
+pub struct PostRenderQueue {
+    pending: Arc<Mutex<Vec<DeferredHooks>>>,
+}
+
+impl PostRenderQueue {
+    pub fn defer<D: Into<Utf8PathBuf>>(&self, destination: D, hooks: Vec<PostRenderHook>) {
+        if !hooks.is_empty() {
+            self.pending
+                .lock()
+                .expect("Lock Error")
+                .push((destination.into(), hooks));
+        }
+    }
+
+    /// Removes and returns every deferred set of hooks, in the order they were deferred.
+    pub fn take(&self) -> Vec<DeferredHooks> {
+        std::mem::take(&mut *self.pending.lock().expect("Lock Error"))
+    }
+}
+
+/// Runs `hooks` one at a time in `destination`, once allowed by the `security.allow_exec` setting or, if that is
+/// unset, by the user.  Progress and failures are reported through the IO driver, and only the failure of a
+/// required hook is returned as an error.  Hooks are skipped in dry runs, and when output is not being written to
+/// the local filesystem.
+pub fn run_post_render_hooks(
+    archetect: &Archetect,
+    render_context: &RenderContext,
+    destination: &Utf8Path,
+    hooks: &[PostRenderHook],
+) -> Result<(), ArchetypeError> {
+    if hooks.is_empty() {
+        return Ok(());
+    }
+
+    if render_context.dry_run() {
+        for hook in hooks {
+            archetect.request(CommandRequest::LogInfo(format!(
+                "Dry Run: skipping post-render command `{}`",
+                hook.command()
+            )));
+        }
+        return Ok(());
+    }
+
+    if !render_context.sink().is_filesystem() {
+        for hook in hooks {
+            archetect.request(CommandRequest::LogInfo(format!(
+                "Skipping post-render command `{}`: output is not being written to the filesystem",
+                hook.command()
+            )));
+        }
+        return Ok(());
+    }
+
+    if !allow_hooks(archetect, destination, hooks)? {
+        for hook in hooks {
+            archetect.request(CommandRequest::LogWarn(format!(
+                "Skipping post-render command `{}`: executing commands is not allowed",
+                hook.command()
+            )));
+        }
+        return Ok(());
+    }
+
+    for hook in hooks {
+        let directory = match hook.directory() {
+            Some(directory) => destination.join(directory),
+            None => destination.to_path_buf(),
+        };
+        archetect.request(CommandRequest::LogInfo(format!(
+            "Running `{}` in '{}'",
+            hook.command(),
+            directory
+        )));
+        if let Err(message) = run_hook(archetect, hook, &directory) {
+            if hook.required() {
+                return Err(ArchetypeError::PostRenderHookFailed {
+                    command: hook.command().to_owned(),
+                    message,
+                });
+            }
+            archetect.request(CommandRequest::LogWarn(format!(
+                "Post-render command `{}` failed: {}",
+                hook.command(),
+                message
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn run_hook(archetect: &Archetect, hook: &PostRenderHook, directory: &Utf8Path) -> Result<(), String> {
+    let command_line = hook
+        .command_line()
+        .ok_or_else(|| "the command is empty or improperly quoted".to_owned())?;
+    if !directory.is_dir() {
+        return Err(format!("'{}' is not a directory", directory));
+    }
+
+    let output = Command::new(&command_line[0])
+        .args(&command_line[1..])
+        .current_dir(directory)
+        .output()
+        .map_err(|err| err.to_string())?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.success() {
+        for output in [stdout.trim(), stderr.trim()] {
+            if !output.is_empty() {
+                archetect.request(CommandRequest::LogDebug(output.to_owned()));
+            }
+        }
+        Ok(())
+    } else {
+        let status = match output.status.code() {
+            Some(code) => format!("exited with code {}", code),
+            None => "was terminated by a signal".to_owned(),
+        };
+        match stderr.trim() {
+            "" => Err(status),
+            stderr => Err(format!("{}:\n{}", status, stderr)),
+        }
+    }
+}
+
+fn allow_hooks(
+    archetect: &Archetect,
+    destination: &Utf8Path,
+    hooks: &[PostRenderHook],
+) -> Result<bool, ArchetypeError> {
+    if let Some(allow_exec) = archetect.configuration().security().allow_exec() {
+        return Ok(allow_exec);
+    }
+    if archetect.is_headless() {
+        return Ok(false);
+    }
+
+    let commands = hooks
+        .iter()
+        .map(|hook| format!("  {}", hook.command()))
+        .collect::<Vec<String>>()
+        .join("\n");
+    let message = format!(
+        "This archetype wants to run the following commands in '{}':\n{}\nAllow?",
+        destination, commands
+    );
+    let prompt_info = BoolPromptInfo::new(message, None::<&str>).with_default(Some(true));
+
+    archetect.request(CommandRequest::PromptForBool(prompt_info));
+    match archetect.response() {
+        CommandResponse::Boolean(allow) => Ok(allow),
+        CommandResponse::None => Ok(false),
+        CommandResponse::Abort => Err(ArchetypeError::OperationInterrupted),
+        CommandResponse::Error(error) => Err(ArchetypeError::PostRenderPromptError(error)),
+        response => Err(ArchetypeError::PostRenderPromptError(format!(
+            "Unexpected response: {:?}",
+            response
+        ))),
+    }
+}
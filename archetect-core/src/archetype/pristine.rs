@@ -0,0 +1,174 @@
+use camino::{Utf8Path, Utf8PathBuf};
+use log::{debug, warn};
+
+use crate::archetype::render_context::RenderContext;
+use crate::archetype::render_plan::PlannedAction;
+use crate::archetype::render_record::RENDER_RECORD_DIRECTORY;
+use crate::errors::RenderError;
+
+pub const PRISTINE_DIRECTORY: &str = "pristine";
+
+/// Locates the unmodified copies of files rendered into a project, kept beside its render record in
+/// `.archetect/pristine`, which serve as the common ancestors when a later render merges into files that may have been
+/// edited since.  Copies are written through the render context, so they are staged along with the rest of a render,
+/// and travel with the project wherever it is moved.
+#[derive(Clone, Debug)]
+pub struct PristineStore {
+    root: Utf8PathBuf,
+    directory: Utf8PathBuf,
+}
+
+impl PristineStore {
+    /// Creates a store for the project rendered into `root`.
+    pub fn new<R: Into<Utf8PathBuf>>(root: R) -> PristineStore {
+        let root = root.into();
+        let directory = root.join(RENDER_RECORD_DIRECTORY).join(PRISTINE_DIRECTORY);
+        PristineStore { root, directory }
+    }
+
+    pub fn root(&self) -> &Utf8Path {
+        &self.root
+    }
+
+    /// The directory holding this project's pristine copies.
+    pub fn directory(&self) -> &Utf8Path {
+        &self.directory
+    }
+
+    /// The location of the pristine copy of `destination`, or `None` if `destination` is outside the project.
+    pub fn location<P: AsRef<Utf8Path>>(&self, destination: P) -> Option<Utf8PathBuf> {
+        let relative = destination.as_ref().strip_prefix(&self.root).ok()?;
+        Some(self.directory.join(relative))
+    }
+}
+
+/// Records `contents` as the pristine copy of `destination`, through the output of `render_context`.
+pub fn write_pristine(render_context: &RenderContext, destination: &Utf8Path, contents: &str) -> Result<(), RenderError> {
+    let Some(location) = render_context.pristine().and_then(|pristine| pristine.location(destination)) else {
+        return Ok(());
+    };
+    if let Some(parent) = location.parent() {
+        render_context.create_output_dir(parent)?;
+    }
+    render_context.write_output(&location, contents.as_bytes())
+}
+
+fn read_pristine(render_context: &RenderContext, destination: &Utf8Path) -> Result<Option<String>, RenderError> {
+    let Some(location) = render_context.pristine().and_then(|pristine| pristine.location(destination)) else {
+        return Ok(None);
+    };
+    if !render_context.output_is_file(&location) {
+        return Ok(None);
+    }
+    let contents = render_context.read_existing(&location)?;
+    Ok(Some(String::from_utf8_lossy(&contents).into_owned()))
+}
+
+/// Three-way merges freshly rendered contents (theirs) into the existing file at `destination` (ours), using the
+/// pristine copy from the previous render as the common ancestor.  Colliding hunks are left in the file with conflict
+/// markers.
+pub fn merge_rendered(
+    render_context: &RenderContext,
+    destination: &Utf8Path,
+    theirs: &str,
+) -> Result<PlannedAction, RenderError> {
+    let ours = String::from_utf8_lossy(&render_context.read_existing(destination)?).into_owned();
+    let base = read_pristine(render_context, destination)?;
+
+    let (action, merged) = match merge_contents(base.as_deref(), &ours, theirs) {
+        MergeOutcome::Unchanged => (PlannedAction::Preserve, None),
+        MergeOutcome::NoAncestor => {
+            warn!("No pristine copy of '{}' to merge with; preserving local contents", destination);
+            (PlannedAction::Preserve, None)
+        }
+        MergeOutcome::Updated(contents) => (PlannedAction::Overwrite, Some(contents)),
+        MergeOutcome::Merged(contents) => (PlannedAction::Merge, Some(contents)),
+        MergeOutcome::Conflicted(contents) => (PlannedAction::Conflict, Some(contents)),
+    };
+    debug!("Merging {:?}: {}", destination, action);
+
+    if !render_context.dry_run() {
+        if let Some(merged) = merged {
+            render_context.write_output(destination, merged.as_bytes())?;
+        }
+        write_pristine(render_context, destination, theirs)?;
+    }
+    Ok(action)
+}
+
+#[derive(Debug, PartialEq)]
+enum MergeOutcome {
+    Unchanged,
+    NoAncestor,
+    Updated(String),
+    Merged(String),
+    Conflicted(String),
+}
+
+fn merge_contents(base: Option<&str>, ours: &str, theirs: &str) -> MergeOutcome {
+    if ours == theirs {
+        return MergeOutcome::Unchanged;
+    }
+    let Some(base) = base else {
+        return MergeOutcome::NoAncestor;
+    };
+    if theirs == base {
+        MergeOutcome::Unchanged
+    } else if ours == base {
+        MergeOutcome::Updated(theirs.to_owned())
+    } else {
+        match diffy::merge(base, ours, theirs) {
+            Ok(merged) => MergeOutcome::Merged(merged),
+            Err(conflicted) => MergeOutcome::Conflicted(conflicted),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_non_overlapping() {
+        let base = "one\ntwo\nthree\nfour\nfive\n";
+        let ours = "one\ntwo (edited)\nthree\nfour\nfive\n";
+        let theirs = "one\ntwo\nthree\nfour\nfive (updated)\n";
+        assert_eq!(
+            merge_contents(Some(base), ours, theirs),
+            MergeOutcome::Merged("one\ntwo (edited)\nthree\nfour\nfive (updated)\n".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_merge_conflict() {
+        let base = "one\ntwo\nthree\n";
+        let ours = "one\ntwo (ours)\nthree\n";
+        let theirs = "one\ntwo (theirs)\nthree\n";
+        match merge_contents(Some(base), ours, theirs) {
+            MergeOutcome::Conflicted(contents) => {
+                assert!(contents.contains("<<<<<<< ours"));
+                assert!(contents.contains("two (ours)"));
+                assert!(contents.contains("two (theirs)"));
+                assert!(contents.contains(">>>>>>> theirs"));
+            }
+            outcome => panic!("Expected a conflict, but got {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn test_merge_without_local_edits() {
+        assert_eq!(
+            merge_contents(Some("one\n"), "one\n", "one\ntwo\n"),
+            MergeOutcome::Updated("one\ntwo\n".to_owned())
+        );
+        assert_eq!(merge_contents(Some("one\n"), "one (edited)\n", "one\n"), MergeOutcome::Unchanged);
+        assert_eq!(merge_contents(None, "one (edited)\n", "one\n"), MergeOutcome::NoAncestor);
+    }
+
+    #[test]
+    fn test_pristine_location() {
+        let pristine = PristineStore::new("project");
+        assert_eq!(pristine.location("project/src/main.rs").unwrap(), "project/.archetect/pristine/src/main.rs");
+        assert!(pristine.location("elsewhere/main.rs").is_none());
+    }
+}
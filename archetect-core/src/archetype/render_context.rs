@@ -1,7 +1,15 @@
 use camino::{Utf8Path, Utf8PathBuf};
 use rhai::{Dynamic, Map};
 use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 use crate::actions::RenderArchetypeInfo;
+use crate::archetype::output_sink::{FilesystemSink, OutputSink};
+use crate::archetype::post_render::PostRenderQueue;
+use crate::archetype::pristine::PristineStore;
+use crate::archetype::render_plan::RenderPlan;
+use crate::archetype::render_record::{AnswerLog, RecordedComponents};
+use crate::archetype::render_transaction::RenderTransaction;
+use crate::errors::RenderError;
 
 #[derive(Clone, Debug)]
 pub struct RenderContext {
@@ -11,6 +19,18 @@ pub struct RenderContext {
     use_defaults_all: bool,
     switches: HashSet<String>,
     settings: Map,
+    dry_run: bool,
+    plan: RenderPlan,
+    transactional: bool,
+    transaction: Option<RenderTransaction>,
+    record: bool,
+    git_init: Option<bool>,
+    answer_log: AnswerLog,
+    recorded_components: RecordedComponents,
+    pristine: Option<PristineStore>,
+    overwrite_all: Arc<Mutex<Option<bool>>>,
+    post_render: PostRenderQueue,
+    sink: Arc<dyn OutputSink>,
 }
 
 impl RenderContext {
@@ -22,6 +42,50 @@ impl RenderContext {
             use_defaults_all: false,
             switches: Default::default(),
             settings: Default::default(),
+            dry_run: false,
+            plan: Default::default(),
+            transactional: true,
+            transaction: None,
+            record: false,
+            git_init: None,
+            answer_log: Default::default(),
+            recorded_components: Default::default(),
+            pristine: None,
+            overwrite_all: Default::default(),
+            post_render: Default::default(),
+            sink: Arc::new(FilesystemSink),
+        }
+    }
+
+    /// Creates a context for rendering a component into `destination`, sharing this context's dry run setting,
+    /// render plan, transaction, pristine copies, overwrite decisions, deferred post-render hooks, and output sink,
+    /// and logging its answers within this context's answer log.  When replaying, the answers recorded for the
+    /// component fill in any not given in `answers`.
+    pub fn component<T: Into<Utf8PathBuf>>(&self, destination: T, answers: Map) -> RenderContext {
+        let destination = destination.into();
+        let mut answers = answers;
+        let recorded_components = match self.recorded_components.take(&destination) {
+            Some(recorded) => {
+                for (key, value) in recorded.answers() {
+                    answers.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+                self.recorded_components.nested(&recorded)
+            }
+            None => Default::default(),
+        };
+        RenderContext {
+            answer_log: self.answer_log.component(destination.clone()),
+            recorded_components,
+            git_init: Some(false),
+            dry_run: self.dry_run,
+            plan: self.plan.clone(),
+            transactional: self.transactional,
+            transaction: self.transaction.clone(),
+            pristine: self.pristine.clone(),
+            overwrite_all: self.overwrite_all.clone(),
+            post_render: self.post_render.clone(),
+            sink: self.sink.clone(),
+            ..RenderContext::new(destination, answers)
         }
     }
 
@@ -50,6 +114,11 @@ impl RenderContext {
         self.answers.clone()
     }
 
+    pub fn with_answers(mut self, answers: Map) -> Self {
+        self.answers = answers;
+        self
+    }
+
     pub fn destination(&self) -> &Utf8Path {
         self.destination.as_path()
     }
@@ -124,6 +193,212 @@ impl RenderContext {
     pub fn set_use_defaults_all(&mut self, value: bool) {
         self.use_defaults_all = value;
     }
+
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    pub fn with_dry_run(mut self, value: bool) -> Self {
+        self.set_dry_run(value);
+        self
+    }
+
+    pub fn set_dry_run(&mut self, value: bool) {
+        self.dry_run = value;
+    }
+
+    pub fn plan(&self) -> &RenderPlan {
+        &self.plan
+    }
+
+    /// Whether output is staged, and only committed into the destination once rendering succeeds, which is the
+    /// default.  While staged, output remains visible to `Path` calls in scripts, but not to commands they run.
+    pub fn transactional(&self) -> bool {
+        self.transactional
+    }
+
+    pub fn with_transactional(mut self, value: bool) -> Self {
+        self.set_transactional(value);
+        self
+    }
+
+    pub fn set_transactional(&mut self, value: bool) {
+        self.transactional = value;
+    }
+
+    /// Whether a render record should be written into the destination once rendering succeeds.  This is never
+    /// inherited by components.
+    pub fn record(&self) -> bool {
+        self.record
+    }
+
+    pub fn with_record(mut self, value: bool) -> Self {
+        self.set_record(value);
+        self
+    }
+
+    pub fn set_record(&mut self, value: bool) {
+        self.record = value;
+    }
+
+    /// Whether a git repository should be initialized in the destination once rendering succeeds, overriding the
+    /// archetype's `git.init` setting when set.  Components never initialize a repository.
+    pub fn git_init(&self) -> Option<bool> {
+        self.git_init
+    }
+
+    pub fn with_git_init(mut self, value: Option<bool>) -> Self {
+        self.set_git_init(value);
+        self
+    }
+
+    pub fn set_git_init(&mut self, value: Option<bool>) {
+        self.git_init = value;
+    }
+
+    pub fn answer_log(&self) -> &AnswerLog {
+        &self.answer_log
+    }
+
+    /// Sets the components recorded by a previous render, whose answers are given to components as they are rendered.
+    pub fn with_recorded_components(mut self, recorded_components: RecordedComponents) -> Self {
+        self.recorded_components = recorded_components;
+        self
+    }
+
+    pub fn pristine(&self) -> Option<&PristineStore> {
+        self.pristine.as_ref()
+    }
+
+    pub fn with_pristine(mut self, pristine: PristineStore) -> Self {
+        self.pristine = Some(pristine);
+        self
+    }
+
+    /// Whether all remaining existing files are to be overwritten (`Some(true)`) or preserved (`Some(false)`), once
+    /// an overwrite prompt has been answered with `All` or `None`.
+    pub fn overwrite_all(&self) -> Option<bool> {
+        *self.overwrite_all.lock().expect("Lock Error")
+    }
+
+    pub fn set_overwrite_all(&self, overwrite: bool) {
+        *self.overwrite_all.lock().expect("Lock Error") = Some(overwrite);
+    }
+
+    pub fn post_render(&self) -> &PostRenderQueue {
+        &self.post_render
+    }
+
+    pub fn sink(&self) -> &dyn OutputSink {
+        self.sink.as_ref()
+    }
+
+    /// Directs output to `sink` rather than to the local filesystem.  Sinks not backed by the filesystem do not take
+    /// part in transactions.
+    pub fn with_sink<S: OutputSink>(mut self, sink: S) -> Self {
+        self.sink = Arc::new(sink);
+        self
+    }
+
+    pub fn transaction(&self) -> Option<&RenderTransaction> {
+        self.transaction.as_ref()
+    }
+
+    pub fn with_transaction(mut self, transaction: RenderTransaction) -> Self {
+        self.transaction = Some(transaction);
+        self
+    }
+
+    /// Where a file destined for `path` should actually be written, which is within the staging directory while a
+    /// transaction is in progress.
+    pub fn output_path<P: AsRef<Utf8Path>>(&self, path: P) -> Utf8PathBuf {
+        match &self.transaction {
+            Some(transaction) => transaction.staged_path(path),
+            None => path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Where the current contents of `path` should be read from, which is its staged copy if an in-progress
+    /// transaction has written one.
+    pub fn existing_path<P: AsRef<Utf8Path>>(&self, path: P) -> Utf8PathBuf {
+        let path = path.as_ref();
+        match &self.transaction {
+            Some(transaction) => {
+                let staged = transaction.staged_path(path);
+                if staged.exists() {
+                    staged
+                } else {
+                    path.to_path_buf()
+                }
+            }
+            None => path.to_path_buf(),
+        }
+    }
+
+    /// Whether `path` exists, taking into account anything staged or removed by an in-progress transaction.
+    pub fn output_exists<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        match &self.transaction {
+            Some(transaction) => transaction.exists(path),
+            None => self.sink.exists(path.as_ref()),
+        }
+    }
+
+    pub fn output_is_file<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        match &self.transaction {
+            Some(transaction) => transaction.is_file(path),
+            None => self.sink.is_file(path.as_ref()),
+        }
+    }
+
+    pub fn output_is_dir<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        match &self.transaction {
+            Some(transaction) => transaction.is_dir(path),
+            None => self.sink.is_dir(path.as_ref()),
+        }
+    }
+
+    /// The current contents of `path`, read from its staged copy if an in-progress transaction has written one.
+    pub fn read_existing<P: AsRef<Utf8Path>>(&self, path: P) -> Result<Vec<u8>, RenderError> {
+        self.sink.read(&self.existing_path(path))
+    }
+
+    pub fn create_output_dir<P: AsRef<Utf8Path>>(&self, path: P) -> Result<(), RenderError> {
+        self.sink.create_dir_all(&self.output_path(path))
+    }
+
+    pub fn write_output<P: AsRef<Utf8Path>>(&self, path: P, contents: &[u8]) -> Result<(), RenderError> {
+        self.sink.write(&self.prepare_output(path)?, contents)
+    }
+
+    /// Writes the contents of the local file at `source` to `path`.
+    pub fn copy_output<S: AsRef<Utf8Path>, P: AsRef<Utf8Path>>(&self, source: S, path: P) -> Result<(), RenderError> {
+        self.sink.copy(source.as_ref(), &self.prepare_output(path)?)
+    }
+
+    pub fn link_output<T: AsRef<Utf8Path>, P: AsRef<Utf8Path>>(&self, target: T, path: P) -> Result<(), RenderError> {
+        self.sink.symlink(target.as_ref(), &self.prepare_output(path)?)
+    }
+
+    /// Where a file destined for `path` should be written, as [RenderContext::output_path], ensuring that its parent
+    /// directory exists within the staging directory while a transaction is in progress.
+    fn prepare_output<P: AsRef<Utf8Path>>(&self, path: P) -> Result<Utf8PathBuf, RenderError> {
+        match &self.transaction {
+            Some(transaction) => transaction.prepare(path),
+            None => Ok(path.as_ref().to_path_buf()),
+        }
+    }
+
+    pub fn set_output_mode<P: AsRef<Utf8Path>>(&self, path: P, mode: u32) -> Result<(), RenderError> {
+        self.sink.set_mode(&self.output_path(path), mode)
+    }
+
+    /// Removes `path`, which is only staged for removal while a transaction is in progress.
+    pub fn remove_output<P: AsRef<Utf8Path>>(&self, path: P) -> Result<(), RenderError> {
+        match &self.transaction {
+            Some(transaction) => transaction.remove(path),
+            None => self.sink.remove(path.as_ref()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+/// The decision made for a single file while rendering a directory.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlannedAction {
+    Create,
+    Overwrite,
+    Preserve,
+    Prompt,
+    Merge,
+    Conflict,
+    Copy,
+    Skip,
+    /// Edited in place by a script, such as by inserting a snippet or merging into a structured file.
+    Edit,
+}
+
+impl Display for PlannedAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlannedAction::Create => write!(f, "create"),
+            PlannedAction::Overwrite => write!(f, "overwrite"),
+            PlannedAction::Preserve => write!(f, "preserve"),
+            PlannedAction::Prompt => write!(f, "prompt"),
+            PlannedAction::Merge => write!(f, "merge"),
+            PlannedAction::Conflict => write!(f, "conflict"),
+            PlannedAction::Copy => write!(f, "copy"),
+            PlannedAction::Skip => write!(f, "skip"),
+            PlannedAction::Edit => write!(f, "edit"),
+        }
+    }
+}
+
+impl PlannedAction {
+    /// Whether carrying out this action writes to the destination file.
+    pub fn writes(&self) -> bool {
+        matches!(
+            self,
+            PlannedAction::Create
+                | PlannedAction::Overwrite
+                | PlannedAction::Merge
+                | PlannedAction::Conflict
+                | PlannedAction::Copy
+                | PlannedAction::Edit
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlannedFile {
+    destination: Utf8PathBuf,
+    source: Utf8PathBuf,
+    action: PlannedAction,
+}
+
+impl PlannedFile {
+    pub fn new<D: Into<Utf8PathBuf>, S: Into<Utf8PathBuf>>(destination: D, source: S, action: PlannedAction) -> Self {
+        PlannedFile {
+            destination: destination.into(),
+            source: source.into(),
+            action,
+        }
+    }
+
+    pub fn destination(&self) -> &Utf8Path {
+        &self.destination
+    }
+
+    pub fn source(&self) -> &Utf8Path {
+        &self.source
+    }
+
+    pub fn action(&self) -> PlannedAction {
+        self.action
+    }
+}
+
+/// Records every file decision made by `render_directory` over the course of a render, including those made by
+/// components.  Clones share the same underlying record.
+#[derive(Clone, Debug, Default)]
+pub struct RenderPlan {
+    entries: Arc<Mutex<Vec<PlannedFile>>>,
+}
+
+impl RenderPlan {
+    pub fn new() -> RenderPlan {
+        Default::default()
+    }
+
+    pub fn record(&self, entry: PlannedFile) {
+        self.entries.lock().expect("Lock Error").push(entry);
+    }
+
+    pub fn entries(&self) -> Vec<PlannedFile> {
+        self.entries.lock().expect("Lock Error").clone()
+    }
+
+    /// Files merged with conflicting hunks, which have been left with conflict markers.
+    pub fn conflicts(&self) -> Vec<PlannedFile> {
+        self.entries()
+            .into_iter()
+            .filter(|entry| entry.action == PlannedAction::Conflict)
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().expect("Lock Error").is_empty()
+    }
+
+    pub fn to_json<P: AsRef<Utf8Path>>(&self, root: P) -> String {
+        let root = root.as_ref();
+        let entries = self
+            .entries()
+            .into_iter()
+            .map(|entry| PlannedFile {
+                destination: relativize(root, &entry.destination),
+                ..entry
+            })
+            .collect::<Vec<PlannedFile>>();
+        let document = PlanDocument {
+            destination: root.to_path_buf(),
+            entries,
+        };
+        serde_json::to_string_pretty(&document).expect("Unexpected error converting RenderPlan to json")
+    }
+
+    pub fn to_tree<P: AsRef<Utf8Path>>(&self, root: P) -> String {
+        let root = root.as_ref();
+        let mut tree = TreeNode::default();
+        for entry in self.entries() {
+            let path = relativize(root, &entry.destination);
+            let mut node = &mut tree;
+            for component in path.components() {
+                node = node.children.entry(component.to_string()).or_default();
+            }
+            node.action = Some(entry.action);
+        }
+
+        let mut output = String::new();
+        output.push_str(root.as_str());
+        output.push('\n');
+        tree.write_children(&mut output, "");
+        output
+    }
+}
+
+#[derive(Serialize)]
+struct PlanDocument {
+    destination: Utf8PathBuf,
+    entries: Vec<PlannedFile>,
+}
+
+#[derive(Default)]
+struct TreeNode {
+    action: Option<PlannedAction>,
+    children: BTreeMap<String, TreeNode>,
+}
+
+impl TreeNode {
+    fn write_children(&self, output: &mut String, prefix: &str) {
+        let count = self.children.len();
+        for (index, (name, child)) in self.children.iter().enumerate() {
+            let last = index + 1 == count;
+            output.push_str(prefix);
+            output.push_str(if last { "└── " } else { "├── " });
+            output.push_str(name);
+            if let Some(action) = child.action {
+                output.push_str(&format!(" [{}]", action));
+            }
+            output.push('\n');
+            let prefix = format!("{}{}", prefix, if last { "    " } else { "│   " });
+            child.write_children(output, &prefix);
+        }
+    }
+}
+
+fn relativize(root: &Utf8Path, path: &Utf8Path) -> Utf8PathBuf {
+    path.strip_prefix(root).map(|p| p.to_path_buf()).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_tree() {
+        let plan = RenderPlan::new();
+        plan.record(PlannedFile::new("out/README.md", "content/README.md", PlannedAction::Create));
+        plan.record(PlannedFile::new("out/src/main.rs", "content/src/main.rs", PlannedAction::Overwrite));
+        plan.record(PlannedFile::new("out/src/logo.png", "content/src/logo.png", PlannedAction::Copy));
+
+        let expected = "out\n\
+            ├── README.md [create]\n\
+            └── src\n    \
+                ├── logo.png [copy]\n    \
+                └── main.rs [overwrite]\n";
+        assert_eq!(plan.to_tree("out"), expected);
+    }
+
+    #[test]
+    fn test_plan_json() {
+        let plan = RenderPlan::new();
+        plan.record(PlannedFile::new("out/src/main.rs", "content/src/main.rs", PlannedAction::Preserve));
+
+        let json: serde_json::Value = serde_json::from_str(&plan.to_json("out")).unwrap();
+        assert_eq!(json["destination"], "out");
+        assert_eq!(json["entries"][0]["destination"], "src/main.rs");
+        assert_eq!(json["entries"][0]["source"], "content/src/main.rs");
+        assert_eq!(json["entries"][0]["action"], "preserve");
+    }
+
+    #[test]
+    fn test_plan_shared_between_clones() {
+        let plan = RenderPlan::new();
+        let clone = plan.clone();
+        clone.record(PlannedFile::new("out/a", "a", PlannedAction::Skip));
+        assert_eq!(plan.entries().len(), 1);
+    }
+}
@@ -0,0 +1,382 @@
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use rhai::{Dynamic, Map};
+use serde::{Deserialize, Serialize};
+
+use crate::archetype::archetype::Archetype;
+use crate::archetype::render_context::RenderContext;
+use crate::errors::RenderError;
+use crate::source::SourceType;
+use crate::Archetect;
+
+pub const RENDER_RECORD_DIRECTORY: &str = ".archetect";
+pub const RENDER_RECORD_FILE: &str = "render.yaml";
+
+/// A description of how a project was rendered, written into the project so that the render can be understood and
+/// reproduced later.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RenderRecord {
+    source: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    gitref: Option<String>,
+    archetect_version: String,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    switches: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    use_defaults: Vec<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    use_defaults_all: bool,
+    #[serde(default)]
+    answers: Map,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    components: Vec<ComponentRecord>,
+}
+
+/// The answers used by a component rendered from within an archetype's script.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ComponentRecord {
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    source: Option<String>,
+    destination: Utf8PathBuf,
+    #[serde(default)]
+    answers: Map,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    components: Vec<ComponentRecord>,
+}
+
+impl RenderRecord {
+    pub fn capture(archetect: &Archetect, archetype: &Archetype, render_context: &RenderContext) -> RenderRecord {
+        let (source, commit, gitref) = match archetype.source() {
+            Some(source) => match source.source_type() {
+                SourceType::RemoteGit { url, .. } => {
+                    // Remote sources may carry their gitref as a fragment, which is recorded separately
+                    let url = url.split('#').next().unwrap_or_default().to_string();
+                    (url, source.commit(), source.gitref())
+                }
+                // Local sources are recorded absolutely, so that replays do not depend on the working directory
+                source_type => {
+                    let path = source_type.local_path();
+                    let path = path.canonicalize_utf8().unwrap_or_else(|_| path.to_path_buf());
+                    (path.to_string(), None, None)
+                }
+            },
+            None => (archetype.root().to_string(), None, None),
+        };
+        let mut switches = render_context.switches().iter().cloned().collect::<Vec<String>>();
+        switches.sort();
+        let mut use_defaults = render_context.use_defaults().iter().cloned().collect::<Vec<String>>();
+        use_defaults.sort();
+
+        RenderRecord {
+            source,
+            commit,
+            gitref,
+            archetect_version: archetect.version().to_string(),
+            switches,
+            use_defaults,
+            use_defaults_all: render_context.use_defaults_all(),
+            answers: render_context.answer_log().answers(),
+            components: render_context.answer_log().components(render_context.destination()),
+        }
+    }
+
+    pub fn location<P: AsRef<Utf8Path>>(destination: P) -> Utf8PathBuf {
+        destination.as_ref().join(RENDER_RECORD_DIRECTORY).join(RENDER_RECORD_FILE)
+    }
+
+    pub fn load<P: AsRef<Utf8Path>>(destination: P) -> Result<RenderRecord, RenderError> {
+        let path = RenderRecord::location(destination);
+        let contents = fs::read_to_string(&path)
+            .map_err(|err| RenderError::FileReadError { path: path.clone(), source: err })?;
+        serde_yaml::from_str(&contents).map_err(|err| RenderError::RecordError { path, source: err })
+    }
+
+    /// Writes this record into the destination of `render_context`, through its output sink.
+    pub fn write(&self, render_context: &RenderContext) -> Result<(), RenderError> {
+        let path = RenderRecord::location(render_context.destination());
+        let parent = path.parent().expect("Record has a parent directory");
+        render_context.create_output_dir(parent)?;
+        let contents = serde_yaml::to_string(self)
+            .map_err(|err| RenderError::RecordError { path: path.clone(), source: err })?;
+        render_context.write_output(&path, contents.as_bytes())
+    }
+
+    /// The source to resolve when replaying this record, at `gitref` if given, or otherwise at the recorded commit,
+    /// so that a replay renders the same archetype code as the original render, falling back to the recorded gitref.
+    pub fn replay_source(&self, gitref: Option<&str>) -> String {
+        match gitref.or(self.commit.as_deref()).or(self.gitref.as_deref()) {
+            Some(gitref) => format!("{}#{}", self.source, gitref),
+            None => self.source.clone(),
+        }
+    }
+
+    /// Adds the recorded answers, switches, and defaults to `render_context` for replaying this record, along with the
+    /// answers recorded for its components.  Answers already present in the context take precedence over recorded
+    /// answers.
+    pub fn apply_to(&self, render_context: RenderContext) -> RenderContext {
+        let destination = render_context.destination().to_path_buf();
+        let mut answers = self.answers.clone();
+        answers.extend(render_context.answers_owned());
+        let mut switches = render_context.switches().clone();
+        switches.extend(self.switches.iter().cloned());
+        let mut use_defaults = render_context.use_defaults().clone();
+        use_defaults.extend(self.use_defaults.iter().cloned());
+        let use_defaults_all = render_context.use_defaults_all() || self.use_defaults_all;
+
+        render_context
+            .with_answers(answers)
+            .with_switches(switches)
+            .with_use_defaults(use_defaults)
+            .with_use_defaults_all(use_defaults_all)
+            .with_recorded_components(RecordedComponents::new(destination, self.components.clone()))
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn commit(&self) -> Option<&str> {
+        self.commit.as_deref()
+    }
+
+    pub fn gitref(&self) -> Option<&str> {
+        self.gitref.as_deref()
+    }
+
+    pub fn archetect_version(&self) -> &str {
+        &self.archetect_version
+    }
+
+    pub fn switches(&self) -> &[String] {
+        &self.switches
+    }
+
+    pub fn use_defaults(&self) -> &[String] {
+        &self.use_defaults
+    }
+
+    pub fn use_defaults_all(&self) -> bool {
+        self.use_defaults_all
+    }
+
+    pub fn answers(&self) -> &Map {
+        &self.answers
+    }
+
+    pub fn components(&self) -> &[ComponentRecord] {
+        &self.components
+    }
+}
+
+impl ComponentRecord {
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    pub fn destination(&self) -> &Utf8Path {
+        &self.destination
+    }
+
+    pub fn answers(&self) -> &Map {
+        &self.answers
+    }
+
+    pub fn components(&self) -> &[ComponentRecord] {
+        &self.components
+    }
+}
+
+/// The components recorded by a previous render, awaiting their answers while the render is replayed.  Clones share
+/// the same components.
+#[derive(Clone, Debug, Default)]
+pub struct RecordedComponents {
+    root: Utf8PathBuf,
+    pending: Arc<Mutex<Vec<ComponentRecord>>>,
+}
+
+impl RecordedComponents {
+    /// Creates the recorded `components` of a render into `root`, which their destinations are relative to.
+    pub fn new<R: Into<Utf8PathBuf>>(root: R, components: Vec<ComponentRecord>) -> RecordedComponents {
+        RecordedComponents {
+            root: root.into(),
+            pending: Arc::new(Mutex::new(components)),
+        }
+    }
+
+    /// Removes and returns the first remaining component recorded as rendered into `destination`, so that a component
+    /// rendered into the same destination more than once is matched with each of its records in turn.
+    pub fn take(&self, destination: &Utf8Path) -> Option<ComponentRecord> {
+        let relative = destination.strip_prefix(&self.root).unwrap_or(destination);
+        let mut pending = self.pending.lock().expect("Lock Error");
+        let index = pending.iter().position(|component| component.destination == relative)?;
+        Some(pending.remove(index))
+    }
+
+    /// The components nested within `component`, taken from these recorded components.
+    pub fn nested(&self, component: &ComponentRecord) -> RecordedComponents {
+        RecordedComponents::new(self.root.clone(), component.components.clone())
+    }
+}
+
+/// Collects the answers actually used by prompts over the course of a render, along with those used by each
+/// component.  Clones share the same log.
+#[derive(Clone, Debug, Default)]
+pub struct AnswerLog {
+    inner: Arc<Mutex<AnswerLogInner>>,
+}
+
+#[derive(Debug, Default)]
+struct AnswerLogInner {
+    source: Option<String>,
+    destination: Utf8PathBuf,
+    answers: Map,
+    components: Vec<AnswerLog>,
+}
+
+impl AnswerLog {
+    pub fn new() -> AnswerLog {
+        Default::default()
+    }
+
+    pub fn record<K: AsRef<str>>(&self, key: K, value: Dynamic) {
+        self.inner.lock().expect("Lock Error").answers.insert(key.as_ref().into(), value);
+    }
+
+    pub fn set_source<S: Into<String>>(&self, source: S) {
+        self.inner.lock().expect("Lock Error").source = Some(source.into());
+    }
+
+    /// Creates a log for a component rendered into `destination`, nested within this one.
+    pub fn component<D: Into<Utf8PathBuf>>(&self, destination: D) -> AnswerLog {
+        let component = AnswerLog {
+            inner: Arc::new(Mutex::new(AnswerLogInner {
+                destination: destination.into(),
+                ..Default::default()
+            })),
+        };
+        self.inner.lock().expect("Lock Error").components.push(component.clone());
+        component
+    }
+
+    pub fn answers(&self) -> Map {
+        self.inner.lock().expect("Lock Error").answers.clone()
+    }
+
+    /// The components rendered so far, with their destinations relative to `root`.
+    pub fn components(&self, root: &Utf8Path) -> Vec<ComponentRecord> {
+        let components = self.inner.lock().expect("Lock Error").components.clone();
+        components
+            .into_iter()
+            .map(|component| {
+                let (source, destination, answers) = {
+                    let inner = component.inner.lock().expect("Lock Error");
+                    (inner.source.clone(), inner.destination.clone(), inner.answers.clone())
+                };
+                ComponentRecord {
+                    source,
+                    destination: destination
+                        .strip_prefix(root)
+                        .map(|path| path.to_path_buf())
+                        .unwrap_or_else(|_| destination.clone()),
+                    answers,
+                    components: component.components(root),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_source() {
+        let record: RenderRecord = serde_yaml::from_str(
+            "source: git@github.com:archetect/example.git\ngitref: v1.0\narchetect_version: 2.0.0\n",
+        )
+        .unwrap();
+        assert_eq!(record.replay_source(None), "git@github.com:archetect/example.git#v1.0");
+        assert_eq!(record.replay_source(Some("v1.1")), "git@github.com:archetect/example.git#v1.1");
+    }
+
+    #[test]
+    fn test_replay_source_uses_commit() {
+        let record: RenderRecord = serde_yaml::from_str(
+            "source: git@github.com:archetect/example.git\ncommit: 0123abcd\ngitref: main\narchetect_version: 2.0.0\n",
+        )
+        .unwrap();
+        assert_eq!(record.replay_source(None), "git@github.com:archetect/example.git#0123abcd");
+        assert_eq!(record.replay_source(Some("v1.1")), "git@github.com:archetect/example.git#v1.1");
+    }
+
+    #[test]
+    fn test_recorded_components() {
+        let record: RenderRecord = serde_yaml::from_str(
+            r#"
+            source: archetypes/example
+            archetect_version: 2.0.0
+            components:
+              - destination: service
+                answers:
+                  port: 8080
+                components:
+                  - destination: service/db
+                    answers:
+                      engine: postgres
+              - destination: service
+                answers:
+                  port: 9090
+            "#,
+        )
+        .unwrap();
+        let render_context = record.apply_to(RenderContext::new("out", Map::new()));
+
+        let mut answers = Map::new();
+        answers.insert("port".into(), Dynamic::from(7070_i64));
+        let first = render_context.component("out/service", answers);
+        assert_eq!(first.answers().get("port").unwrap().as_int().unwrap(), 7070);
+        let nested = first.component("out/service/db", Map::new());
+        assert_eq!(nested.answers().get("engine").unwrap().to_string(), "postgres");
+
+        let second = render_context.component("out/service", Map::new());
+        assert_eq!(second.answers().get("port").unwrap().as_int().unwrap(), 9090);
+        let third = render_context.component("out/service", Map::new());
+        assert!(third.answers().is_empty());
+    }
+
+    #[test]
+    fn test_replay_context() {
+        let record: RenderRecord = serde_yaml::from_str(
+            "source: archetypes/example\narchetect_version: 2.0.0\nswitches: [build]\nanswers:\n  name: example\n",
+        )
+        .unwrap();
+        let mut answers = Map::new();
+        answers.insert("name".into(), "override".into());
+        let render_context = record.apply_to(RenderContext::new("out", answers).with_switch("test"));
+        assert_eq!(record.replay_source(None), "archetypes/example");
+        assert!(render_context.switches().contains("build"));
+        assert!(render_context.switches().contains("test"));
+        assert_eq!(render_context.answers().get("name").unwrap().to_string(), "override");
+    }
+
+    #[test]
+    fn test_answer_log_components() {
+        let log = AnswerLog::new();
+        log.record("name", "example".into());
+        let component = log.component("out/service");
+        component.set_source("https://github.com/archetect/component.git");
+        component.record("port", Dynamic::from(8080_i64));
+
+        let components = log.components(Utf8Path::new("out"));
+        assert_eq!(log.answers().get("name").unwrap().to_string(), "example");
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].destination(), "service");
+        assert_eq!(components[0].source(), Some("https://github.com/archetect/component.git"));
+        assert_eq!(components[0].answers().get("port").unwrap().as_int().unwrap(), 8080);
+    }
+}
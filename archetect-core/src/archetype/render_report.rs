@@ -0,0 +1,166 @@
+use std::fs;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use rhai::Map;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::archetype::render_context::RenderContext;
+use crate::archetype::render_plan::PlannedAction;
+use crate::archetype::render_record::ComponentRecord;
+use crate::errors::RenderError;
+
+/// A machine-readable account of a completed render, listing every file in the render plan along with the answers
+/// and components used, for tooling to assert on what was generated.
+#[derive(Clone, Debug, Serialize)]
+pub struct RenderReport {
+    destination: Utf8PathBuf,
+    dry_run: bool,
+    answers: Map,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    components: Vec<ComponentRecord>,
+    files: Vec<ReportedFile>,
+}
+
+/// A file from the render plan, with its destination relative to the render destination.  Files present at their
+/// destination once the render has completed are described by their size and the SHA-256 hash of their contents.
+#[derive(Clone, Debug, Serialize)]
+pub struct ReportedFile {
+    destination: Utf8PathBuf,
+    source: Utf8PathBuf,
+    action: PlannedAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
+}
+
+/// The formats a [RenderReport] can be written in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Yaml,
+}
+
+impl ReportFormat {
+    /// YAML for paths ending in `.yaml` or `.yml`, and JSON otherwise.
+    pub fn from_path<P: AsRef<Utf8Path>>(path: P) -> ReportFormat {
+        let extension = path.as_ref().extension().unwrap_or_default().to_ascii_lowercase();
+        match extension.as_str() {
+            "yaml" | "yml" => ReportFormat::Yaml,
+            _ => ReportFormat::Json,
+        }
+    }
+}
+
+impl RenderReport {
+    /// Describes the render carried out with `render_context`, which must have completed.
+    pub fn capture(render_context: &RenderContext) -> Result<RenderReport, RenderError> {
+        let root = render_context.destination();
+        let files = render_context
+            .plan()
+            .entries()
+            .into_iter()
+            .map(|entry| {
+                let (size, sha256) = if render_context.dry_run() || entry.action() == PlannedAction::Skip {
+                    (None, None)
+                } else {
+                    describe_contents(render_context, entry.destination())?
+                };
+                Ok(ReportedFile {
+                    destination: entry
+                        .destination()
+                        .strip_prefix(root)
+                        .map(|path| path.to_path_buf())
+                        .unwrap_or_else(|_| entry.destination().to_path_buf()),
+                    source: entry.source().to_path_buf(),
+                    action: entry.action(),
+                    size,
+                    sha256,
+                })
+            })
+            .collect::<Result<Vec<ReportedFile>, RenderError>>()?;
+
+        Ok(RenderReport {
+            destination: root.to_path_buf(),
+            dry_run: render_context.dry_run(),
+            answers: render_context.answer_log().answers(),
+            components: render_context.answer_log().components(root),
+            files,
+        })
+    }
+
+    pub fn files(&self) -> &[ReportedFile] {
+        &self.files
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Unexpected error converting RenderReport to json")
+    }
+
+    pub fn to_yaml(&self) -> String {
+        serde_yaml::to_string(self).expect("Unexpected error converting RenderReport to yaml")
+    }
+
+    /// Writes this report to `path`, in the format implied by its extension.
+    pub fn write<P: AsRef<Utf8Path>>(&self, path: P) -> Result<(), RenderError> {
+        let path = path.as_ref();
+        let contents = match ReportFormat::from_path(path) {
+            ReportFormat::Json => self.to_json(),
+            ReportFormat::Yaml => self.to_yaml(),
+        };
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .map_err(|err| RenderError::CreateDirectoryError { path: parent.to_path_buf(), source: err })?;
+        }
+        fs::write(path, contents).map_err(|err| RenderError::WriteError { path: path.to_path_buf(), source: err })
+    }
+}
+
+impl ReportedFile {
+    pub fn destination(&self) -> &Utf8Path {
+        &self.destination
+    }
+
+    pub fn source(&self) -> &Utf8Path {
+        &self.source
+    }
+
+    pub fn action(&self) -> PlannedAction {
+        self.action
+    }
+
+    pub fn size(&self) -> Option<u64> {
+        self.size
+    }
+
+    pub fn sha256(&self) -> Option<&str> {
+        self.sha256.as_deref()
+    }
+}
+
+/// The size and SHA-256 hash of the regular file at `path` in the render's output, if there is one.  Links are not
+/// followed.
+fn describe_contents(
+    render_context: &RenderContext,
+    path: &Utf8Path,
+) -> Result<(Option<u64>, Option<String>), RenderError> {
+    if path.is_symlink() || !render_context.output_is_file(path) {
+        return Ok((None, None));
+    }
+    let contents = render_context.read_existing(path)?;
+    Ok((Some(contents.len() as u64), Some(format!("{:x}", Sha256::digest(&contents)))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_format() {
+        assert_eq!(ReportFormat::from_path("report.json"), ReportFormat::Json);
+        assert_eq!(ReportFormat::from_path("out/report.YML"), ReportFormat::Yaml);
+        assert_eq!(ReportFormat::from_path("report.yaml"), ReportFormat::Yaml);
+        assert_eq!(ReportFormat::from_path("report"), ReportFormat::Json);
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::HashSet;
+
+use camino::Utf8Path;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+use crate::archetype::archetype::RuleAction;
+use crate::archetype::archetype_manifest::{LineEndings, TemplatingRule};
+use crate::errors::ArchetypeError;
+
+/// The `templating.rules` of an archetype's manifest, along with its `templating.binary` and `templating.text`
+/// patterns, with their glob patterns compiled.
+#[derive(Clone, Debug, Default)]
+pub struct RenderRules {
+    rules: Vec<CompiledRule>,
+    binary: GlobSet,
+    text: GlobSet,
+}
+
+#[derive(Clone, Debug)]
+struct CompiledRule {
+    globs: GlobSet,
+    action: Option<RuleAction>,
+    mode: Option<u32>,
+    line_endings: Option<LineEndings>,
+    trailing_newline: Option<bool>,
+    if_switch: Option<String>,
+}
+
+impl CompiledRule {
+    fn enabled(&self, switches: &HashSet<String>) -> bool {
+        match &self.if_switch {
+            Some(switch) => switches.contains(switch),
+            None => true,
+        }
+    }
+}
+
+impl RenderRules {
+    pub fn new(rules: &[TemplatingRule]) -> Result<RenderRules, ArchetypeError> {
+        let mut compiled = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let globs = compile_globs(rule.patterns())?;
+            let mode = match rule.mode() {
+                Some(mode) => Some(
+                    u32::from_str_radix(mode, 8)
+                        .ok()
+                        .filter(|mode| *mode <= 0o7777)
+                        .ok_or_else(|| ArchetypeError::TemplatingRuleModeError { mode: mode.to_owned() })?,
+                ),
+                None => None,
+            };
+            compiled.push(CompiledRule {
+                globs,
+                action: rule.action(),
+                mode,
+                line_endings: rule.line_endings(),
+                trailing_newline: rule.trailing_newline(),
+                if_switch: rule.if_switch().map(ToOwned::to_owned),
+            });
+        }
+        Ok(RenderRules {
+            rules: compiled,
+            ..Default::default()
+        })
+    }
+
+    /// Sets the patterns of files always treated as binary and copied, or as text and rendered, in place of
+    /// inspecting their contents.  Binary patterns take precedence over text patterns.
+    pub fn with_content_types(mut self, binary: &[String], text: &[String]) -> Result<RenderRules, ArchetypeError> {
+        self.binary = compile_globs(binary)?;
+        self.text = compile_globs(text)?;
+        Ok(self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// The action of the first rule with an action matching `path`, relative to the directory being rendered, or
+    /// `None` if no such rule matches.  A matching rule whose switch is not enabled skips the path.
+    pub fn action<P: AsRef<Utf8Path>>(&self, path: P, switches: &HashSet<String>) -> Option<RuleAction> {
+        let path = path.as_ref();
+        self.rules
+            .iter()
+            .filter(|rule| rule.action.is_some())
+            .find(|rule| rule.globs.is_match(path.as_std_path()))
+            .and_then(|rule| if rule.enabled(switches) { rule.action } else { Some(RuleAction::SKIP) })
+    }
+
+    /// The mode of the first enabled rule with a mode matching `path`, relative to the directory being rendered.
+    pub fn mode<P: AsRef<Utf8Path>>(&self, path: P, switches: &HashSet<String>) -> Option<u32> {
+        self.first_setting(path.as_ref(), switches, |rule| rule.mode)
+    }
+
+    /// The line endings of the first enabled rule with line endings matching `path`.
+    pub fn line_endings<P: AsRef<Utf8Path>>(&self, path: P, switches: &HashSet<String>) -> Option<LineEndings> {
+        self.first_setting(path.as_ref(), switches, |rule| rule.line_endings)
+    }
+
+    /// The trailing newline setting of the first enabled rule with one matching `path`.
+    pub fn trailing_newline<P: AsRef<Utf8Path>>(&self, path: P, switches: &HashSet<String>) -> Option<bool> {
+        self.first_setting(path.as_ref(), switches, |rule| rule.trailing_newline)
+    }
+
+    /// Whether `path`, relative to the directory being rendered, matches a `templating.binary` pattern.
+    pub fn is_binary<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        self.binary.is_match(path.as_ref().as_std_path())
+    }
+
+    /// Whether `path`, relative to the directory being rendered, matches a `templating.text` pattern.
+    pub fn is_text<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        self.text.is_match(path.as_ref().as_std_path())
+    }
+
+    fn first_setting<T, F: Fn(&CompiledRule) -> Option<T>>(
+        &self,
+        path: &Utf8Path,
+        switches: &HashSet<String>,
+        setting: F,
+    ) -> Option<T> {
+        self.rules
+            .iter()
+            .filter(|rule| rule.enabled(switches) && rule.globs.is_match(path.as_std_path()))
+            .find_map(setting)
+    }
+}
+
+fn compile_globs(patterns: &[String]) -> Result<GlobSet, ArchetypeError> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|err| ArchetypeError::TemplatingRuleError {
+                pattern: pattern.to_owned(),
+                source: err,
+            })?;
+        builder.add(glob);
+    }
+    builder.build().map_err(|err| ArchetypeError::TemplatingRuleError {
+        pattern: patterns.join(", "),
+        source: err,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = RenderRules::new(&[
+            TemplatingRule::new(vec![".github/workflows/*.yml"]).with_action(RuleAction::COPY),
+            TemplatingRule::new(vec!["**/*.orig", ".github/**"]).with_action(RuleAction::SKIP),
+        ])
+        .unwrap();
+        let switches = HashSet::new();
+
+        assert_eq!(rules.action(".github/workflows/build.yml", &switches), Some(RuleAction::COPY));
+        assert_eq!(rules.action(".github/workflows/nested/build.yml", &switches), Some(RuleAction::SKIP));
+        assert_eq!(rules.action("src/main.rs.orig", &switches), Some(RuleAction::SKIP));
+        assert_eq!(rules.action("src/main.rs", &switches), None);
+    }
+
+    #[test]
+    fn test_switched_rule() {
+        let rules = RenderRules::new(&[TemplatingRule::new(vec!["docker/**"])
+            .with_action(RuleAction::RENDER)
+            .with_if_switch("docker")])
+        .unwrap();
+
+        assert_eq!(rules.action("docker/Dockerfile", &HashSet::new()), Some(RuleAction::SKIP));
+        let switches = HashSet::from(["docker".to_owned()]);
+        assert_eq!(rules.action("docker/Dockerfile", &switches), Some(RuleAction::RENDER));
+    }
+
+    #[test]
+    fn test_modes() {
+        let rules = RenderRules::new(&[
+            TemplatingRule::new(vec!["vendor/**"]).with_action(RuleAction::COPY),
+            TemplatingRule::new(vec!["bin/*.sh", "gradlew"]).with_mode("0755"),
+        ])
+        .unwrap();
+        let switches = HashSet::new();
+
+        assert_eq!(rules.mode("bin/build.sh", &switches), Some(0o755));
+        assert_eq!(rules.mode("gradlew", &switches), Some(0o755));
+        assert_eq!(rules.mode("vendor/lib.js", &switches), None);
+        // Rules without an action do not affect how files are rendered
+        assert_eq!(rules.action("gradlew", &switches), None);
+    }
+
+    #[test]
+    fn test_line_ending_overrides() {
+        let rules = RenderRules::new(&[
+            TemplatingRule::new(vec!["**/*.bat", "**/*.cmd"]).with_line_endings(LineEndings::Crlf),
+            TemplatingRule::new(vec!["**/*.bat"]).with_trailing_newline(false),
+            TemplatingRule::new(vec!["**"]).with_trailing_newline(true),
+        ])
+        .unwrap();
+        let switches = HashSet::new();
+
+        assert_eq!(rules.line_endings("gradlew.bat", &switches), Some(LineEndings::Crlf));
+        assert_eq!(rules.line_endings("gradlew", &switches), None);
+        assert_eq!(rules.trailing_newline("gradlew.bat", &switches), Some(false));
+        assert_eq!(rules.trailing_newline("bin/build.cmd", &switches), Some(true));
+    }
+
+    #[test]
+    fn test_content_types() {
+        let rules = RenderRules::new(&[])
+            .unwrap()
+            .with_content_types(&["**/*.ico".to_owned(), "assets/**".to_owned()], &["assets/**/*.svg".to_owned()])
+            .unwrap();
+
+        assert!(rules.is_binary("favicon.ico"));
+        assert!(rules.is_binary("assets/logo.svg"));
+        assert!(rules.is_text("assets/logo.svg"));
+        assert!(!rules.is_binary("src/main.rs"));
+        assert!(!rules.is_text("src/main.rs"));
+
+        let result = RenderRules::new(&[]).unwrap().with_content_types(&[], &["src/[a-".to_owned()]);
+        assert!(matches!(result, Err(ArchetypeError::TemplatingRuleError { .. })));
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let result = RenderRules::new(&[TemplatingRule::new(vec!["src/[a-"]).with_action(RuleAction::COPY)]);
+        assert!(matches!(result, Err(ArchetypeError::TemplatingRuleError { .. })));
+
+        let result = RenderRules::new(&[TemplatingRule::new(vec!["gradlew"]).with_mode("0789")]);
+        assert!(matches!(result, Err(ArchetypeError::TemplatingRuleModeError { .. })));
+    }
+}
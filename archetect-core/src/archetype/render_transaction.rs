@@ -0,0 +1,425 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::sync::{Arc, Mutex};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use log::{debug, warn};
+use tempfile::TempDir;
+
+use crate::archetype::archetype::create_symlink;
+use crate::errors::RenderError;
+
+/// Stages everything written beneath a destination into a temporary directory, so that a render can be committed
+/// into the destination once it has succeeded, or discarded if it fails.  Clones share the same staging directory.
+///
+/// Only output written through the render context is staged, which includes the files and edits of `Path` calls in
+/// scripts.  Commands run by scripts, such as through `execute`, see the destination as it was before the render.
+#[derive(Clone, Debug)]
+pub struct RenderTransaction {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    destination: Utf8PathBuf,
+    staging: Mutex<Option<TempDir>>,
+    staging_path: Utf8PathBuf,
+    removals: Mutex<Vec<Utf8PathBuf>>,
+}
+
+impl RenderTransaction {
+    pub fn begin<D: Into<Utf8PathBuf>>(destination: D) -> Result<RenderTransaction, RenderError> {
+        let destination = destination.into();
+        let staging = temp_dir_beside(&destination, "staging")?;
+        // The destination is staged within the temporary directory, so that, like the destination, it only exists once
+        // something creates it
+        let staging_path = Utf8PathBuf::from_path_buf(staging.path().join("root")).expect("valid UTF-8 encoded path");
+        debug!("Staging render of {} in {}", destination, staging_path);
+
+        Ok(RenderTransaction {
+            inner: Arc::new(Inner {
+                destination,
+                staging: Mutex::new(Some(staging)),
+                staging_path,
+                removals: Default::default(),
+            }),
+        })
+    }
+
+    pub fn destination(&self) -> &Utf8Path {
+        &self.inner.destination
+    }
+
+    /// The location within the staging directory that stands in for `path`, or `path` itself if it does not
+    /// reside within the destination.
+    pub fn staged_path<P: AsRef<Utf8Path>>(&self, path: P) -> Utf8PathBuf {
+        let path = path.as_ref();
+        match path.strip_prefix(&self.inner.destination) {
+            Ok(relative) => self.inner.staging_path.join(relative),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+
+    /// The location within the staging directory that stands in for `path`, as [RenderTransaction::staged_path],
+    /// creating its parent directories within the staging directory, which may be missing where the destination
+    /// already has them.
+    pub fn prepare<P: AsRef<Utf8Path>>(&self, path: P) -> Result<Utf8PathBuf, RenderError> {
+        let staged = self.staged_path(path.as_ref());
+        if staged.as_path() != path.as_ref() {
+            if let Some(parent) = staged.parent() {
+                fs::create_dir_all(parent)
+                    .map_err(|err| RenderError::CreateDirectoryError { path: parent.to_path_buf(), source: err })?;
+            }
+        }
+        Ok(staged)
+    }
+
+    pub fn exists<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        let path = path.as_ref();
+        self.staged_path(path).exists() || (!self.is_removed(path) && path.exists())
+    }
+
+    pub fn is_file<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        let path = path.as_ref();
+        self.staged_path(path).is_file() || (!self.is_removed(path) && path.is_file())
+    }
+
+    pub fn is_dir<P: AsRef<Utf8Path>>(&self, path: P) -> bool {
+        let path = path.as_ref();
+        self.staged_path(path).is_dir() || (!self.is_removed(path) && path.is_dir())
+    }
+
+    /// Discards anything staged at `path`, and schedules `path` to be removed from the destination on commit.
+    pub fn remove<P: AsRef<Utf8Path>>(&self, path: P) -> Result<(), RenderError> {
+        let path = path.as_ref();
+        remove_path(&self.staged_path(path))?;
+        self.inner.removals.lock().expect("Lock Error").push(path.to_path_buf());
+        Ok(())
+    }
+
+    fn is_removed(&self, path: &Utf8Path) -> bool {
+        self.inner
+            .removals
+            .lock()
+            .expect("Lock Error")
+            .iter()
+            .any(|removed| path.starts_with(removed))
+    }
+
+    /// Applies scheduled removals and moves everything staged into the destination.  Entries of the destination that
+    /// are removed or replaced are first moved aside, so that if the commit fails part way, the destination is
+    /// restored as it was, and the staged output is kept for inspection.
+    pub fn commit(&self) -> Result<(), RenderError> {
+        let staging = self.inner.staging.lock().expect("Lock Error").take();
+        let Some(staging) = staging else {
+            return Ok(());
+        };
+        let removals = std::mem::take(&mut *self.inner.removals.lock().expect("Lock Error"));
+
+        // An empty destination stands for the current directory
+        let destination = match self.inner.destination.as_str() {
+            "" => Utf8Path::new("."),
+            _ => self.inner.destination.as_path(),
+        };
+        if !destination.exists() {
+            if let Some(parent) = destination.parent().filter(|parent| !parent.as_str().is_empty()) {
+                fs::create_dir_all(parent)
+                    .map_err(|err| RenderError::CreateDirectoryError { path: parent.to_path_buf(), source: err })?;
+            }
+            if fs::rename(&self.inner.staging_path, destination).is_ok() {
+                debug!("Committed render to {}", destination);
+                // The staging directory now lives at the destination, and must not be cleaned up
+                let _ = staging.keep();
+                return Ok(());
+            }
+        }
+
+        let mut journal = Journal::begin(destination)?;
+        let result = removals
+            .iter()
+            .try_for_each(|removal| journal.back_up(removal))
+            .and_then(|_| {
+                if !self.inner.staging_path.is_dir() {
+                    return Ok(());
+                }
+                if !destination.is_dir() {
+                    journal.create_dir(destination)?;
+                }
+                move_contents(&self.inner.staging_path, destination, &mut journal)
+            });
+        match result {
+            Ok(()) => {
+                debug!("Committed render to {}", destination);
+                Ok(())
+            }
+            Err(err) => {
+                journal.rollback();
+                let _ = staging.keep();
+                Err(RenderError::CommitError {
+                    destination: destination.to_path_buf(),
+                    staging: self.inner.staging_path.clone(),
+                    source: Box::new(err),
+                })
+            }
+        }
+    }
+}
+
+/// Creates a temporary directory for `purpose`, preferably alongside `destination`, so that moving entries between
+/// the two is a rename rather than a copy.
+fn temp_dir_beside(destination: &Utf8Path, purpose: &str) -> Result<TempDir, RenderError> {
+    match destination.parent().filter(|parent| !parent.as_str().is_empty() && parent.is_dir()) {
+        Some(parent) => tempfile::Builder::new()
+            .prefix(&format!(".archetect-{}-", purpose))
+            .tempdir_in(parent),
+        None => tempfile::Builder::new().prefix(&format!("archetect-{}-", purpose)).tempdir(),
+    }
+    .map_err(|err| RenderError::CreateDirectoryError { path: destination.to_path_buf(), source: err })
+}
+
+/// The steps taken while committing a transaction, so that they may be undone if the commit fails.
+struct Journal {
+    backups: TempDir,
+    backups_path: Utf8PathBuf,
+    steps: Vec<Step>,
+}
+
+enum Step {
+    /// A staged entry moved into the destination, or copied if `copied`, leaving the staged entry in place.
+    Moved {
+        staged: Utf8PathBuf,
+        target: Utf8PathBuf,
+        copied: bool,
+    },
+    /// A directory created within the destination.
+    Created { target: Utf8PathBuf },
+    /// An entry of the destination moved aside, before being removed or replaced.
+    BackedUp { target: Utf8PathBuf, backup: Utf8PathBuf },
+}
+
+impl Journal {
+    fn begin(destination: &Utf8Path) -> Result<Journal, RenderError> {
+        let backups = temp_dir_beside(destination, "backup")?;
+        let backups_path = Utf8PathBuf::from_path_buf(backups.path().to_path_buf()).expect("valid UTF-8 encoded path");
+        Ok(Journal {
+            backups,
+            backups_path,
+            steps: vec![],
+        })
+    }
+
+    /// Moves the entry at `target` aside, if there is one.
+    fn back_up(&mut self, target: &Utf8Path) -> Result<(), RenderError> {
+        if target.symlink_metadata().is_err() {
+            return Ok(());
+        }
+        let backup = self.backups_path.join(self.steps.len().to_string());
+        fs::rename(target, &backup).map_err(|err| RenderError::MoveError {
+            from: target.to_path_buf(),
+            to: backup.clone(),
+            source: err,
+        })?;
+        self.steps.push(Step::BackedUp {
+            target: target.to_path_buf(),
+            backup,
+        });
+        Ok(())
+    }
+
+    fn create_dir(&mut self, target: &Utf8Path) -> Result<(), RenderError> {
+        fs::create_dir(target)
+            .map_err(|err| RenderError::CreateDirectoryError { path: target.to_path_buf(), source: err })?;
+        self.steps.push(Step::Created {
+            target: target.to_path_buf(),
+        });
+        Ok(())
+    }
+
+    /// Moves the staged file or link at `staged` to `target`, where nothing exists any longer.
+    fn move_in(&mut self, staged: &Utf8Path, target: &Utf8Path, is_symlink: bool) -> Result<(), RenderError> {
+        let copied = if fs::rename(staged, target).is_ok() {
+            false
+        } else {
+            // Staging may live on a different device than the destination
+            if is_symlink {
+                let link = staged
+                    .read_link_utf8()
+                    .map_err(|err| RenderError::FileReadError { path: staged.to_path_buf(), source: err })?;
+                create_symlink(link, target)?;
+            } else {
+                fs::copy(staged, target).map_err(|err| RenderError::CopyError {
+                    from: staged.to_path_buf(),
+                    to: target.to_path_buf(),
+                    source: err,
+                })?;
+            }
+            true
+        };
+        self.steps.push(Step::Moved {
+            staged: staged.to_path_buf(),
+            target: target.to_path_buf(),
+            copied,
+        });
+        Ok(())
+    }
+
+    /// Undoes every step taken, in reverse.  Should any step fail to be undone, the backups are kept, so that nothing
+    /// from the destination is lost.
+    fn rollback(self) {
+        let mut restored = true;
+        for step in self.steps.into_iter().rev() {
+            let result = match &step {
+                Step::Moved { staged, target, copied: false } => fs::rename(target, staged),
+                Step::Moved { target, copied: true, .. } => fs::remove_file(target),
+                Step::Created { target } => fs::remove_dir(target),
+                Step::BackedUp { target, backup } => fs::rename(backup, target),
+            };
+            if let Err(err) = result {
+                warn!("Error undoing {}: {}", step, err);
+                restored = false;
+            }
+        }
+        if !restored {
+            let backups = self.backups.keep();
+            warn!("Entries replaced in the destination are kept in {}", backups.display());
+        }
+    }
+}
+
+impl std::fmt::Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Step::Moved { staged, target, .. } => write!(f, "move of {} to {}", staged, target),
+            Step::Created { target } => write!(f, "creation of {}", target),
+            Step::BackedUp { target, backup } => write!(f, "backup of {} to {}", target, backup),
+        }
+    }
+}
+
+fn move_contents(source: &Utf8Path, destination: &Utf8Path, journal: &mut Journal) -> Result<(), RenderError> {
+    for entry in source
+        .read_dir_utf8()
+        .map_err(|err| RenderError::DirectoryListError { path: source.to_path_buf(), source: err })?
+    {
+        let entry = entry.map_err(|err| RenderError::DirectoryReadError { path: source.to_path_buf(), source: err })?;
+        let target = destination.join(entry.file_name());
+        let file_type = entry
+            .file_type()
+            .map_err(|err| RenderError::DirectoryReadError { path: source.to_path_buf(), source: err })?;
+        if file_type.is_dir() {
+            // Directories are merged into, unless something other than a directory stands in their place
+            if target.is_symlink() || (target.symlink_metadata().is_ok() && !target.is_dir()) {
+                journal.back_up(&target)?;
+            }
+            if !target.is_dir() {
+                journal.create_dir(&target)?;
+            }
+            move_contents(entry.path(), &target, journal)?;
+        } else {
+            journal.back_up(&target)?;
+            journal.move_in(entry.path(), &target, file_type.is_symlink())?;
+        }
+    }
+    Ok(())
+}
+
+fn remove_path(path: &Utf8Path) -> Result<(), RenderError> {
+    // Links are removed themselves, rather than whatever they point to
+    let result = if path.is_dir() && !path.is_symlink() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    match result {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(RenderError::RemoveError { path: path.to_path_buf(), source: err }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_into_new_destination() {
+        let temp = tempfile::tempdir().unwrap();
+        let destination = Utf8PathBuf::from_path_buf(temp.path().join("project")).unwrap();
+        let transaction = RenderTransaction::begin(&destination).unwrap();
+
+        let staged = transaction.staged_path(destination.join("src/main.rs"));
+        fs::create_dir_all(staged.parent().unwrap()).unwrap();
+        fs::write(&staged, "fn main() {}").unwrap();
+
+        assert!(!destination.exists());
+        assert!(transaction.is_file(destination.join("src/main.rs")));
+
+        transaction.commit().unwrap();
+        assert_eq!(fs::read_to_string(destination.join("src/main.rs")).unwrap(), "fn main() {}");
+    }
+
+    #[test]
+    fn test_commit_into_existing_destination() {
+        let temp = tempfile::tempdir().unwrap();
+        let destination = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+        fs::write(destination.join("existing.txt"), "existing").unwrap();
+        fs::write(destination.join("obsolete.txt"), "obsolete").unwrap();
+
+        let transaction = RenderTransaction::begin(&destination).unwrap();
+        fs::create_dir(transaction.staged_path(&destination)).unwrap();
+        fs::write(transaction.staged_path(destination.join("new.txt")), "new").unwrap();
+        transaction.remove(destination.join("obsolete.txt")).unwrap();
+
+        assert!(transaction.exists(destination.join("existing.txt")));
+        assert!(!transaction.exists(destination.join("obsolete.txt")));
+        assert!(destination.join("obsolete.txt").exists());
+
+        transaction.commit().unwrap();
+        assert_eq!(fs::read_to_string(destination.join("existing.txt")).unwrap(), "existing");
+        assert_eq!(fs::read_to_string(destination.join("new.txt")).unwrap(), "new");
+        assert!(!destination.join("obsolete.txt").exists());
+    }
+
+    #[test]
+    fn test_rollback_restores_destination() {
+        let temp = tempfile::tempdir().unwrap();
+        let destination = Utf8PathBuf::from_path_buf(temp.path().join("project")).unwrap();
+        fs::create_dir_all(destination.join("src")).unwrap();
+        fs::write(destination.join("src/main.rs"), "existing").unwrap();
+        fs::write(destination.join("obsolete.txt"), "obsolete").unwrap();
+
+        let staging = tempfile::tempdir().unwrap();
+        let staging = Utf8PathBuf::from_path_buf(staging.path().to_path_buf()).unwrap();
+        fs::create_dir_all(staging.join("src/nested")).unwrap();
+        fs::write(staging.join("src/main.rs"), "staged").unwrap();
+        fs::write(staging.join("src/nested/lib.rs"), "staged").unwrap();
+
+        let mut journal = Journal::begin(&destination).unwrap();
+        journal.back_up(&destination.join("obsolete.txt")).unwrap();
+        move_contents(&staging, &destination, &mut journal).unwrap();
+        assert_eq!(fs::read_to_string(destination.join("src/main.rs")).unwrap(), "staged");
+        assert!(!destination.join("obsolete.txt").exists());
+
+        journal.rollback();
+        assert_eq!(fs::read_to_string(destination.join("src/main.rs")).unwrap(), "existing");
+        assert_eq!(fs::read_to_string(destination.join("obsolete.txt")).unwrap(), "obsolete");
+        assert!(!destination.join("src/nested").exists());
+        assert_eq!(fs::read_to_string(staging.join("src/main.rs")).unwrap(), "staged");
+        assert_eq!(fs::read_to_string(staging.join("src/nested/lib.rs")).unwrap(), "staged");
+    }
+
+    #[test]
+    fn test_discard_on_drop() {
+        let temp = tempfile::tempdir().unwrap();
+        let destination = Utf8PathBuf::from_path_buf(temp.path().join("project")).unwrap();
+        let transaction = RenderTransaction::begin(&destination).unwrap();
+        let staging = transaction.staged_path(&destination);
+        assert!(!transaction.exists(&destination));
+        fs::create_dir(&staging).unwrap();
+        fs::write(staging.join("file.txt"), "contents").unwrap();
+
+        drop(transaction);
+        assert!(!staging.exists());
+        assert!(!destination.exists());
+    }
+}
@@ -0,0 +1,353 @@
+use camino::Utf8Path;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use toml_edit::{DocumentMut, InlineTable, Item, TableLike};
+
+use crate::errors::ArchetypeScriptError;
+
+/// The structured formats that existing destination files can be loaded from and merged into.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum StructuredFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl StructuredFormat {
+    /// The format implied by the extension of `path`, if it is one of `.json`, `.yaml`, `.yml`, or `.toml`.
+    pub fn from_path<P: AsRef<Utf8Path>>(path: P) -> Option<StructuredFormat> {
+        let extension = path.as_ref().extension()?.to_ascii_lowercase();
+        StructuredFormat::from_name(&extension)
+    }
+
+    pub fn from_name(name: &str) -> Option<StructuredFormat> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(StructuredFormat::Json),
+            "yaml" | "yml" => Some(StructuredFormat::Yaml),
+            "toml" => Some(StructuredFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// Parses `contents`, which are those of the file at `path`, into a JSON value for handing to scripts.
+pub fn load_structured(path: &str, format: StructuredFormat, contents: &str) -> Result<Value, ArchetypeScriptError> {
+    let error = |message: String| ArchetypeScriptError::EditError { path: path.to_owned(), message };
+    match format {
+        StructuredFormat::Json => serde_json::from_str(contents).map_err(|err| error(err.to_string())),
+        StructuredFormat::Yaml => serde_yaml::from_str(contents).map_err(|err| error(err.to_string())),
+        StructuredFormat::Toml => toml::from_str(contents).map_err(|err| error(err.to_string())),
+    }
+}
+
+/// Deep-merges `patch` into `contents`, which are those of the file at `path`, returning the merged document, or
+/// `None` if `patch` is already reflected in it.  Maps are merged key by key, keeping the order of existing keys,
+/// array elements not already present are appended, and other values are replaced.  A null value in `patch` removes
+/// the corresponding key.  TOML documents are edited in place, keeping their comments and formatting, while JSON and
+/// YAML documents are re-serialized, so their comments and custom formatting are not kept.
+pub fn merge_structured(
+    path: &str,
+    format: StructuredFormat,
+    contents: &str,
+    patch: &Value,
+) -> Result<Option<String>, ArchetypeScriptError> {
+    let error = |message: String| ArchetypeScriptError::EditError { path: path.to_owned(), message };
+    let empty = contents.trim().is_empty();
+
+    match format {
+        StructuredFormat::Json => {
+            let mut document = if empty {
+                OrderedJson::Object(Default::default())
+            } else {
+                serde_json::from_str(contents).map_err(|err| error(err.to_string()))?
+            };
+            let original = document.clone();
+            merge_json(&mut document, patch).map_err(|err| error(err.to_string()))?;
+            if document == original && !empty {
+                return Ok(None);
+            }
+            let mut merged = serde_json::to_string_pretty(&document).map_err(|err| error(err.to_string()))?;
+            merged.push('\n');
+            Ok(Some(merged))
+        }
+        StructuredFormat::Yaml => {
+            let mut document = if empty {
+                serde_yaml::Value::Mapping(Default::default())
+            } else {
+                serde_yaml::from_str(contents).map_err(|err| error(err.to_string()))?
+            };
+            let original = document.clone();
+            merge_yaml(&mut document, patch).map_err(|err| error(err.to_string()))?;
+            if document == original && !empty {
+                return Ok(None);
+            }
+            serde_yaml::to_string(&document).map(Some).map_err(|err| error(err.to_string()))
+        }
+        StructuredFormat::Toml => {
+            let Value::Object(patch) = patch else {
+                return Err(error("TOML documents may only be merged with maps".to_owned()));
+            };
+            let mut document = contents.parse::<DocumentMut>().map_err(|err| error(err.to_string()))?;
+            merge_toml_table(document.as_table_mut(), patch, true).map_err(error)?;
+            let merged = document.to_string();
+            if merged == contents && !empty {
+                return Ok(None);
+            }
+            Ok(Some(merged))
+        }
+    }
+}
+
+/// A JSON document that keeps the order of its keys, so that merging into it leaves existing keys where they were,
+/// without enabling the `preserve_order` feature of `serde_json` for everything else that uses it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum OrderedJson {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(String),
+    Array(Vec<OrderedJson>),
+    Object(IndexMap<String, OrderedJson>),
+}
+
+fn merge_json(target: &mut OrderedJson, patch: &Value) -> Result<(), serde_json::Error> {
+    match (target, patch) {
+        (OrderedJson::Object(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.shift_remove(key);
+                } else if let Some(existing) = target.get_mut(key) {
+                    merge_json(existing, value)?;
+                } else {
+                    target.insert(key.clone(), serde_json::from_value(value.clone())?);
+                }
+            }
+        }
+        (OrderedJson::Array(target), Value::Array(patch)) => {
+            for value in patch {
+                let value = serde_json::from_value(value.clone())?;
+                if !target.contains(&value) {
+                    target.push(value);
+                }
+            }
+        }
+        (target, patch) => *target = serde_json::from_value(patch.clone())?,
+    }
+    Ok(())
+}
+
+fn merge_yaml(target: &mut serde_yaml::Value, patch: &Value) -> Result<(), serde_yaml::Error> {
+    match (target, patch) {
+        (serde_yaml::Value::Mapping(target), Value::Object(patch)) => {
+            for (key, value) in patch {
+                if value.is_null() {
+                    target.shift_remove(key.as_str());
+                } else if let Some(existing) = target.get_mut(key.as_str()) {
+                    merge_yaml(existing, value)?;
+                } else {
+                    target.insert(key.as_str().into(), serde_yaml::to_value(value)?);
+                }
+            }
+        }
+        (serde_yaml::Value::Sequence(target), Value::Array(patch)) => {
+            for value in patch {
+                let value = serde_yaml::to_value(value)?;
+                if !target.contains(&value) {
+                    target.push(value);
+                }
+            }
+        }
+        (target, patch) => *target = serde_yaml::to_value(patch)?,
+    }
+    Ok(())
+}
+
+/// Merges `patch` into a TOML table, which is the document itself if `root`, where new tables are added as
+/// `[table]` sections rather than inline.
+fn merge_toml_table(
+    target: &mut dyn TableLike,
+    patch: &serde_json::Map<String, Value>,
+    root: bool,
+) -> Result<(), String> {
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else if let Some(existing) = target.get_mut(key) {
+            merge_toml(existing, value)?;
+        } else {
+            let value = toml_value(value)?;
+            let item = match value {
+                toml_edit::Value::InlineTable(table) if root => Item::Table(table.into_table()),
+                value => Item::Value(value),
+            };
+            target.insert(key, item);
+        }
+    }
+    Ok(())
+}
+
+fn merge_toml(target: &mut Item, patch: &Value) -> Result<(), String> {
+    match patch {
+        Value::Object(patch) if target.is_table_like() => {
+            let table = target.as_table_like_mut().expect("table-like item");
+            merge_toml_table(table, patch, false)
+        }
+        Value::Array(patch) if target.is_array() => {
+            let target = target.as_array_mut().expect("array item");
+            for value in patch {
+                let value = toml_value(value)?;
+                if !target.iter().any(|existing| same_toml_value(existing, &value)) {
+                    target.push(value);
+                }
+            }
+            Ok(())
+        }
+        patch => {
+            let mut value = toml_value(patch)?;
+            match target.as_value() {
+                Some(existing) if same_toml_value(existing, &value) => {}
+                Some(existing) => {
+                    // Keep any comment trailing the value being replaced
+                    *value.decor_mut() = existing.decor().clone();
+                    *target = Item::Value(value);
+                }
+                None => *target = Item::Value(value),
+            }
+            Ok(())
+        }
+    }
+}
+
+fn toml_value(value: &Value) -> Result<toml_edit::Value, String> {
+    let value = match value {
+        Value::Null => return Err("TOML has no null value".to_owned()),
+        Value::Bool(value) => (*value).into(),
+        Value::Number(number) => match (number.as_i64(), number.as_f64()) {
+            (Some(value), _) => value.into(),
+            (None, Some(value)) => value.into(),
+            (None, None) => return Err(format!("{} is out of range for TOML", number)),
+        },
+        Value::String(value) => value.as_str().into(),
+        Value::Array(values) => values
+            .iter()
+            .map(toml_value)
+            .collect::<Result<toml_edit::Array, String>>()?
+            .into(),
+        Value::Object(map) => {
+            let mut table = InlineTable::new();
+            for (key, value) in map.iter().filter(|(_, value)| !value.is_null()) {
+                table.insert(key, toml_value(value)?);
+            }
+            table.into()
+        }
+    };
+    Ok(value)
+}
+
+/// Whether two TOML values are equal, disregarding their formatting.
+fn same_toml_value(left: &toml_edit::Value, right: &toml_edit::Value) -> bool {
+    use toml_edit::Value::*;
+    match (left, right) {
+        (String(left), String(right)) => left.value() == right.value(),
+        (Integer(left), Integer(right)) => left.value() == right.value(),
+        (Float(left), Float(right)) => left.value() == right.value(),
+        (Boolean(left), Boolean(right)) => left.value() == right.value(),
+        (Datetime(left), Datetime(right)) => left.value() == right.value(),
+        (Array(left), Array(right)) => {
+            left.len() == right.len() && left.iter().zip(right.iter()).all(|(left, right)| same_toml_value(left, right))
+        }
+        (InlineTable(left), InlineTable(right)) => {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .all(|(key, value)| right.get(key).is_some_and(|other| same_toml_value(value, other)))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(StructuredFormat::from_path("Cargo.toml"), Some(StructuredFormat::Toml));
+        assert_eq!(StructuredFormat::from_path("docker-compose.YML"), Some(StructuredFormat::Yaml));
+        assert_eq!(StructuredFormat::from_path("package.json"), Some(StructuredFormat::Json));
+        assert_eq!(StructuredFormat::from_path("README.md"), None);
+    }
+
+    #[test]
+    fn test_merge_json_preserves_order() {
+        let contents = r#"{"name": "example", "version": "1.0.0", "dependencies": {"react": "18"}, "files": ["dist"]}"#;
+        let patch = json!({"dependencies": {"axios": "1"}, "files": ["dist", "lib"], "version": null});
+        let merged = merge_structured("package.json", StructuredFormat::Json, contents, &patch).unwrap().unwrap();
+        assert_eq!(
+            merged,
+            "{\n  \"name\": \"example\",\n  \"dependencies\": {\n    \"react\": \"18\",\n    \"axios\": \"1\"\n  },\n  \
+             \"files\": [\n    \"dist\",\n    \"lib\"\n  ]\n}\n"
+        );
+        let patch = json!({"dependencies": {"axios": "1"}});
+        assert!(merge_structured("package.json", StructuredFormat::Json, &merged, &patch).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_merge_yaml() {
+        let contents = "services:\n  web:\n    image: nginx\n";
+        let patch = json!({"services": {"db": {"image": "postgres"}}});
+        let merged = merge_structured("compose.yaml", StructuredFormat::Yaml, contents, &patch).unwrap().unwrap();
+        assert_eq!(merged, "services:\n  web:\n    image: nginx\n  db:\n    image: postgres\n");
+    }
+
+    #[test]
+    fn test_merge_toml() {
+        let contents = "[package]\nname = \"example\"\n\n[dependencies]\nserde = \"1\"\n";
+        let patch = json!({"dependencies": {"anyhow": "1"}});
+        let merged = merge_structured("Cargo.toml", StructuredFormat::Toml, contents, &patch).unwrap().unwrap();
+        assert_eq!(merged, "[package]\nname = \"example\"\n\n[dependencies]\nserde = \"1\"\nanyhow = \"1\"\n");
+        assert!(merge_structured("Cargo.toml", StructuredFormat::Toml, &merged, &patch).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_merge_toml_keeps_comments() {
+        let contents = indoc::indoc! {r#"
+            # The example package
+            [package]
+            name = "example"
+            version = "0.1.0" # bumped on release
+
+            [dependencies]
+            # Serialization
+            serde = { version = "1", features = ["derive"] }
+        "#};
+        let patch = json!({
+            "package": {"version": "0.2.0"},
+            "dependencies": {"serde": {"features": ["rc"]}, "anyhow": "1"},
+            "profile": {"release": {"lto": true}},
+        });
+        let merged = merge_structured("Cargo.toml", StructuredFormat::Toml, contents, &patch).unwrap().unwrap();
+        assert_eq!(
+            merged,
+            indoc::indoc! {r#"
+                # The example package
+                [package]
+                name = "example"
+                version = "0.2.0" # bumped on release
+
+                [dependencies]
+                # Serialization
+                serde = { version = "1", features = ["derive", "rc"] }
+                anyhow = "1"
+
+                [profile]
+                release = { lto = true }
+            "#}
+        );
+        assert!(merge_structured("Cargo.toml", StructuredFormat::Toml, &merged, &patch).unwrap().is_none());
+    }
+}
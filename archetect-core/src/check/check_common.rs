@@ -1,32 +1,33 @@
 use crate::errors::ArchetectError;
-use std::process::Command;
 use super::CHECK_SUCCESS;
 use super::CHECK_ERROR;
 use super::CHECK_PREFIX;
 
 pub fn perform_checks() -> Result<(), ArchetectError> {
-    check_git_installed()?;
+    check_git_support()?;
     check_git_author()?;
     Ok(())
 }
 
-pub fn check_git_installed() -> Result<(), ArchetectError> {
-    println!("\n{CHECK_PREFIX} Checking Git Installation");
+pub fn check_git_support() -> Result<(), ArchetectError> {
+    println!("\n{CHECK_PREFIX} Checking Git Support");
 
-    match  Command::new("git").arg("--version").output() {
-        Ok(output) => {
-            if output.status.success() {
-                println!("\t{CHECK_SUCCESS} {}", String::from_utf8(output.stdout.trim_ascii().to_owned()).expect("UTF-8 Version String"));
-            } else {
-                println!("\t{CHECK_ERROR} Git was found, but returned an unexpected Status Code: {}",  output.status.code().unwrap());
+    let version = git2::Version::get();
+    let (major, minor, rev) = version.libgit2_version();
+    let missing = [("https", version.https()), ("ssh", version.ssh())]
+        .into_iter()
+        .filter(|(_, supported)| !supported)
+        .map(|(protocol, _)| protocol)
+        .collect::<Vec<&str>>();
+    if missing.is_empty() {
+        println!("\t{CHECK_SUCCESS} Built-in Git support (libgit2 {major}.{minor}.{rev}, https, ssh)");
+    } else {
+        println!(
+            "\t{CHECK_ERROR} Built-in Git support (libgit2 {major}.{minor}.{rev}) lacks {}",
+            missing.join(" and ")
+        );
 
-                println!("\n\t Ensure that git is installed correctly.");
-            }
-        }
-        Err(_error) => {
-            println!("\tGit is required, but was not found on the PATH");
-            println!("\n\t Ensure that git is installed correctly.");
-        }
+        println!("\n\t Archetypes and Catalogs cannot be fetched from git URLs using these protocols.");
     }
     Ok(())
 }
@@ -56,7 +57,7 @@ pub fn check_git_author() -> Result<(), ArchetectError> {
 
 #[cfg(test)]
 mod tests {
-    use crate::check::check_common::{perform_checks, check_git_installed};
+    use crate::check::check_common::{perform_checks, check_git_support};
 
     #[test]
     fn test_check_git() {
@@ -64,8 +65,8 @@ mod tests {
     }
 
     #[test]
-    fn test_git_version() {
-        check_git_installed().expect("Working Code");
+    fn test_git_support() {
+        check_git_support().expect("Working Code");
     }
 
 
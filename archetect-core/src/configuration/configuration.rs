@@ -54,6 +54,11 @@ impl Configuration {
         &self.security
     }
 
+    pub fn with_allow_exec(mut self, value: bool) -> Self {
+        self.security = self.security.with_allow_exec(value);
+        self
+    }
+
     pub fn actions(&self) -> &LinkedHashMap<String, ArchetectAction> {
         &self.actions
     }
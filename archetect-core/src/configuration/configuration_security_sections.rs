@@ -10,6 +10,11 @@ impl ConfigurationSecuritySection {
     pub fn allow_exec(&self) -> Option<bool> {
        self.allow_exec.clone()
     }
+
+    pub fn with_allow_exec(mut self, value: bool) -> Self {
+        self.allow_exec = Some(value);
+        self
+    }
 }
 
 impl Default for ConfigurationSecuritySection {
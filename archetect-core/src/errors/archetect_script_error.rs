@@ -107,7 +107,34 @@ pub enum ArchetypeScriptError {
     #[error("The path '{path}' contains path manipulation patterns, which are not allowed. Rendering and other file operations are restricted to the destination directory")]
     PathManipulationError {
         path: String,
-    }
+    },
+    #[error("'{path}' could not be found in the archetype's content or templates directory")]
+    FileNotFound {
+        path: String,
+    },
+    #[error("'{path}' cannot be edited, because it does not exist or is not a file")]
+    EditTargetNotFound {
+        path: String,
+    },
+    #[error("The anchor '{anchor}' could not be found in '{path}'")]
+    EditAnchorNotFound {
+        path: String,
+        anchor: String,
+    },
+    #[error("The anchor '{anchor}' is not a valid regular expression: {message}")]
+    EditAnchorInvalid {
+        anchor: String,
+        message: String,
+    },
+    #[error("The format of '{path}' could not be determined from its extension, and must be given as the 'format' setting")]
+    EditFormatUnknown {
+        path: String,
+    },
+    #[error("Error editing '{path}': {message}")]
+    EditError {
+        path: String,
+        message: String,
+    },
 }
 
 impl ArchetypeScriptError {
@@ -125,6 +152,12 @@ impl ArchetypeScriptError {
             UnexpectedPromptResponse { .. } | KeyedUnexpectedPromptResponse { .. } => "Unexpected Response",
             ArchetypeScriptError::RenderDestinationTypeError { .. } => "Invalid Destination",
             ArchetypeScriptError::PathManipulationError { .. } => "Path Error",
+            ArchetypeScriptError::FileNotFound { .. } => "File Not Found",
+            ArchetypeScriptError::EditTargetNotFound { .. }
+            | ArchetypeScriptError::EditAnchorNotFound { .. }
+            | ArchetypeScriptError::EditAnchorInvalid { .. }
+            | ArchetypeScriptError::EditFormatUnknown { .. }
+            | ArchetypeScriptError::EditError { .. } => "Edit Error",
         }
     }
 
@@ -142,6 +175,12 @@ impl ArchetypeScriptError {
             KeyedInvalidSetSetting { .. } => ErrorType::Function,
             ArchetypeScriptError::RenderDestinationTypeError { .. } => ErrorType::Function,
             ArchetypeScriptError::PathManipulationError { .. } => ErrorType::Function,
+            ArchetypeScriptError::FileNotFound { .. } => ErrorType::Function,
+            ArchetypeScriptError::EditTargetNotFound { .. }
+            | ArchetypeScriptError::EditAnchorNotFound { .. }
+            | ArchetypeScriptError::EditAnchorInvalid { .. }
+            | ArchetypeScriptError::EditFormatUnknown { .. }
+            | ArchetypeScriptError::EditError { .. } => ErrorType::Function,
         }
     }
 
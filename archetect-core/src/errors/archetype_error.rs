@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use camino::Utf8PathBuf;
 
-use crate::errors::{RequirementsError, SourceError};
+use crate::errors::{RenderError, RequirementsError, SourceError};
 
 #[derive(Debug, thiserror::Error)]
 pub enum ArchetypeError {
@@ -38,4 +38,21 @@ pub enum ArchetypeError {
     RequirementsError(#[from] RequirementsError),
     #[error("Archetype Script Aborted")]
     ScriptAbortError,
+    #[error(transparent)]
+    RenderError(#[from] RenderError),
+    #[error("Invalid templating rule pattern `{pattern}`: {source}")]
+    TemplatingRuleError {
+        pattern: String,
+        source: globset::Error,
+    },
+    #[error("Invalid templating rule mode `{mode}`: expected an octal mode such as \"0755\"")]
+    TemplatingRuleModeError { mode: String },
+    #[error("Post-render command `{command}` runs in `{directory}`, which is not a path within the destination")]
+    PostRenderHookDirectoryError { command: String, directory: Utf8PathBuf },
+    #[error("Post-render command `{command}` failed: {message}")]
+    PostRenderHookFailed { command: String, message: String },
+    #[error("Error prompting to run post-render commands: {0}")]
+    PostRenderPromptError(String),
+    #[error("Error initializing a git repository in `{path}`: {source}")]
+    GitInitError { path: Utf8PathBuf, source: git2::Error },
 }
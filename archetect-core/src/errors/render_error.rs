@@ -8,6 +8,16 @@ pub enum RenderError {
     InvalidPathCharacters {
         path: PathBuf,
     },
+    #[error("Path `{path}` renders to `{rendered}`, which is not a path within its parent directory")]
+    PathManipulationError {
+        path: Utf8PathBuf,
+        rendered: String,
+    },
+    #[error("Link `{path}` targets `{target}`, which is not a path within the destination")]
+    LinkTargetError {
+        path: Utf8PathBuf,
+        target: Utf8PathBuf,
+    },
     #[error( "Unable to render path `{path}`: {source}")]
     PathRenderError2 {
         path: PathBuf,
@@ -28,6 +38,18 @@ pub enum RenderError {
         to: Utf8PathBuf,
         source: std::io::Error,
     },
+    #[error("Error moving {from} to {to}: {source}")]
+    MoveError{
+        from: Utf8PathBuf,
+        to: Utf8PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Error committing render to `{destination}`, which has been restored, leaving the staged render in `{staging}`: {source}")]
+    CommitError{
+        destination: Utf8PathBuf,
+        staging: Utf8PathBuf,
+        source: Box<RenderError>,
+    },
     #[error("Error writing to `{path}`: {source}")]
     WriteError{
         path: Utf8PathBuf,
@@ -58,4 +80,42 @@ pub enum RenderError {
         path: Utf8PathBuf,
         source: std::io::Error,
     },
+    #[error("Error removing `{path}`: {source}")]
+    RemoveError{
+        path: Utf8PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Error linking `{link}` to `{target}`: {source}")]
+    SymlinkError{
+        link: Utf8PathBuf,
+        target: Utf8PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Error setting permissions of `{path}`: {source}")]
+    PermissionsError{
+        path: Utf8PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Error prompting to overwrite `{path}`: {message}")]
+    OverwritePromptError{
+        path: Utf8PathBuf,
+        message: String,
+    },
+    #[error("Rendering was interrupted")]
+    OperationInterrupted,
+    #[error("Invalid ignore patterns in `{path}`: {source}")]
+    IgnoreError{
+        path: Utf8PathBuf,
+        source: ignore::Error,
+    },
+    #[error("Error in render record `{path}`: {source}")]
+    RecordError{
+        path: Utf8PathBuf,
+        source: serde_yaml::Error,
+    },
+    #[error("Error writing archive `{path}`: {message}")]
+    ArchiveError{
+        path: Utf8PathBuf,
+        message: String,
+    },
 }
@@ -10,8 +10,18 @@ pub enum SourceError {
     SourceInvalidPath(String),
     #[error("Invalid Source Encoding: `{0}`")]
     SourceInvalidEncoding(String),
-    #[error("Remote Source Error: `{0}`")]
-    RemoteSourceError(String),
+    #[error("Error cloning `{url}`: {source}")]
+    CloneError { url: String, source: git2::Error },
+    #[error("Error fetching `{url}`: {source}")]
+    FetchError { url: String, source: git2::Error },
+    #[error("Git reference `{gitref}` was not found in `{url}`")]
+    GitRefNotFound { url: String, gitref: String },
+    #[error("Error checking out `{gitref}` from `{url}`: {source}")]
+    CheckoutError {
+        url: String,
+        gitref: String,
+        source: git2::Error,
+    },
     #[error("Remote Source is not cached, and Archetect was run in offline mode: `{0}`")]
     OfflineAndNotCached(String),
     #[error("Source IO Error: `{0}`")]
@@ -29,8 +29,8 @@ pub(crate) fn create_engine(
     modules::archetect_module::register(&mut engine, archetect.clone(), archetype.clone(), render_context.clone());
     modules::utils_module::register(&mut engine, archetect.clone(), &render_context);
     modules::cases_module::register(&mut engine);
-    modules::path_module::register(&mut engine, render_context.clone());
-    modules::exec_module::register(&mut engine, archetect.clone(), archetype.clone());
+    modules::path_module::register(&mut engine, archetect.clone(), archetype.clone(), render_context.clone());
+    modules::exec_module::register(&mut engine, archetect.clone(), archetype.clone(), render_context.clone());
     modules::formats_module::register(&mut engine);
     modules::log_module::register(&mut engine, archetect.clone());
     modules::pair_module::register(&mut engine);
@@ -48,6 +48,13 @@ pub(crate) fn create_engine(
         archetype.clone(),
         render_context.clone(),
     );
+    modules::file_module::register(
+        &mut engine,
+        environment.clone(),
+        archetect.clone(),
+        archetype.clone(),
+        render_context.clone(),
+    );
     modules::archetype_module::register(
         &mut engine,
         archetype.clone(),
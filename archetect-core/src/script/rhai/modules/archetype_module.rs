@@ -45,7 +45,7 @@ impl ArchetypeFacade {
 
 pub fn render(archetype: &mut ArchetypeFacade, answers: Map) -> Result<Dynamic, Box<EvalAltResult>> {
     let destination = archetype.render_context.destination().to_path_buf();
-    let render_context = RenderContext::new(destination, answers);
+    let render_context = archetype.render_context.component(destination, answers);
     let result = archetype.child
         .render(render_context)
         .map_err(|err| {
@@ -59,7 +59,7 @@ pub fn render(archetype: &mut ArchetypeFacade, answers: Map) -> Result<Dynamic,
 
 pub fn render_with_settings(archetype: &mut ArchetypeFacade, answers: Map, settings: Map) -> Result<Dynamic, Box<EvalAltResult>> {
     let destination = archetype.render_context.destination().to_path_buf();
-    let mut render_context = RenderContext::new(destination, answers).with_settings(settings.clone());
+    let mut render_context = archetype.render_context.component(destination, answers).with_settings(settings.clone());
     extract_render_context_settings(&mut render_context, &settings);
 
     let result = archetype.child
@@ -78,7 +78,7 @@ pub fn render_with_destination(call: NativeCallContext, archetype: &mut Archetyp
         .render_context
         .destination()
         .join(restrict_path_manipulation(&call, destination)?);
-    let render_context = RenderContext::new(destination, answers);
+    let render_context = archetype.render_context.component(destination, answers);
     let result = archetype.child
         .render(render_context)
         .map_err(|err| {
@@ -95,7 +95,7 @@ pub fn render_with_path(call: NativeCallContext, archetype: &mut ArchetypeFacade
         .render_context
         .destination()
         .join(restrict_path_manipulation(&call, destination.path())?);
-    let render_context = RenderContext::new(destination, answers);
+    let render_context = archetype.render_context.component(destination, answers);
     let result = archetype.child
         .render(render_context)
         .map_err(|err| {
@@ -118,7 +118,7 @@ pub fn render_with_destination_and_settings(
         .render_context
         .destination()
         .join(restrict_path_manipulation(&call, destination)?);
-    let mut render_context = RenderContext::new(destination, answers).with_settings(settings.clone());
+    let mut render_context = archetype.render_context.component(destination, answers).with_settings(settings.clone());
     extract_render_context_settings(&mut render_context, &settings);
     let result = archetype.child
         .render(render_context)
@@ -144,7 +144,7 @@ pub fn render_with_path_and_settings(
         .render_context
         .destination()
         .join(restrict_path_manipulation(&call, destination.path())?);
-    let mut render_context = RenderContext::new(destination, answers).with_settings(settings.clone());
+    let mut render_context = archetype.render_context.component(destination, answers).with_settings(settings.clone());
     extract_render_context_settings(&mut render_context, &settings);
     let result = archetype.child
         .render(render_context)
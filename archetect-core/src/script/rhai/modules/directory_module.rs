@@ -7,7 +7,7 @@ use archetect_templating::Environment;
 use crate::archetype::archetype::{render_directory, Archetype, OverwritePolicy};
 use crate::archetype::render_context::RenderContext;
 use crate::Archetect;
-use crate::errors::{ArchetypeScriptError, ArchetypeScriptErrorWrapper};
+use crate::errors::{ArchetypeScriptError, ArchetypeScriptErrorWrapper, RenderError};
 use crate::script::rhai::modules::path_module::Path;
 use crate::utils::restrict_path_manipulation;
 
@@ -70,12 +70,14 @@ pub fn render(directory: &mut Directory, context: Map) -> Result<(), Box<EvalAlt
     render_directory(
         &directory.environment,
         &directory.archetect,
+        &directory.archetype,
+        &directory.render_context,
         &context,
         source,
         destination,
         OverwritePolicy::Preserve,
     )
-        .map_err(|err| Box::new(EvalAltResult::ErrorSystem("Rendering Error".into(), Box::new(err))))
+        .map_err(render_error)
 }
 
 
@@ -90,12 +92,14 @@ pub fn render_with_settings(directory: &mut Directory, context: Map, settings: M
     render_directory(
         &directory.environment,
         &directory.archetect,
+        &directory.archetype,
+        &directory.render_context,
         &context,
         source,
         destination,
         overwrite_policy,
     )
-        .map_err(|err| Box::new(EvalAltResult::ErrorSystem("Rendering Error".into(), Box::new(err))))
+        .map_err(render_error)
 }
 
 pub fn render_with_destination(call: NativeCallContext, directory: &mut Directory, destination: Dynamic, context: Map) -> Result<(), Box<EvalAltResult>> {
@@ -118,12 +122,14 @@ pub fn render_with_destination(call: NativeCallContext, directory: &mut Director
     render_directory(
         &directory.environment,
         &directory.archetect,
+        &directory.archetype,
+        &directory.render_context,
         &context,
         source,
         destination,
         Default::default(),
     )
-        .map_err(|err| Box::new(EvalAltResult::ErrorSystem("Rendering Error".into(), Box::new(err))))
+        .map_err(render_error)
 }
 
 pub fn render_with_path(call: NativeCallContext, directory: &mut Directory, mut destination: Path, context: Map) -> Result<(), Box<EvalAltResult>> {
@@ -135,12 +141,14 @@ pub fn render_with_path(call: NativeCallContext, directory: &mut Directory, mut
     render_directory(
         &directory.environment,
         &directory.archetect,
+        &directory.archetype,
+        &directory.render_context,
         &context,
         source,
         destination,
         Default::default(),
     )
-        .map_err(|err| Box::new(EvalAltResult::ErrorSystem("Rendering Error".into(), Box::new(err))))
+        .map_err(render_error)
 }
 
 pub fn render_with_path_and_settings(
@@ -163,12 +171,14 @@ pub fn render_with_path_and_settings(
     render_directory(
         &directory.environment,
         &directory.archetect,
+        &directory.archetype,
+        &directory.render_context,
         &context,
         source,
         destination,
         overwrite_policy,
     )
-        .map_err(|err| Box::new(EvalAltResult::ErrorSystem("Rendering Error".into(), Box::new(err))))
+        .map_err(render_error)
 }
 
 
@@ -192,12 +202,22 @@ pub fn render_with_destination_and_settings(
     render_directory(
         &directory.environment,
         &directory.archetect,
+        &directory.archetype,
+        &directory.render_context,
         &context,
         source,
         destination,
         overwrite_policy,
     )
-        .map_err(|err| Box::new(EvalAltResult::ErrorSystem("Rendering Error".into(), Box::new(err))))
+        .map_err(render_error)
+}
+
+pub(crate) fn render_error(error: RenderError) -> Box<EvalAltResult> {
+    match error {
+        // Interruptions abort the script without being reported as errors
+        RenderError::OperationInterrupted => Box::new(EvalAltResult::ErrorTerminated(Dynamic::UNIT, Position::NONE)),
+        error => Box::new(EvalAltResult::ErrorSystem("Rendering Error".into(), Box::new(error))),
+    }
 }
 
 #[allow(non_snake_case)]
@@ -209,4 +229,5 @@ pub mod module {
     pub const Overwrite: OverwritePolicy = OverwritePolicy::Overwrite;
     pub const Preserve: OverwritePolicy = OverwritePolicy::Preserve;
     pub const Prompt: OverwritePolicy = OverwritePolicy::Prompt;
+    pub const Merge: OverwritePolicy = OverwritePolicy::Merge;
 }
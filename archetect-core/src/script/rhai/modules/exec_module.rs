@@ -1,37 +1,42 @@
 use std::process::Command;
 
-use log::warn;
+use log::{info, warn};
 use rhai::{Dynamic, Engine, EvalAltResult, Map, NativeCallContext};
 use archetect_inquire::{Confirm, InquireError};
 
 use crate::archetype::archetype::Archetype;
+use crate::archetype::render_context::RenderContext;
 use crate::Archetect;
 
-pub fn register(engine: &mut Engine, archetect: Archetect, archetype: Archetype) {
+pub fn register(engine: &mut Engine, archetect: Archetect, archetype: Archetype, render_context: RenderContext) {
     let archetect_clone = archetect.clone();
     let archetype_clone = archetype.clone();
+    let rc_clone = render_context.clone();
     engine.register_fn("execute", move |call: NativeCallContext, program: &str| {
-        execute(call, archetect_clone.clone(), archetype_clone.clone(), program)
+        execute(call, archetect_clone.clone(), archetype_clone.clone(), &rc_clone, program)
     });
     let archetect_clone = archetect.clone();
     let archetype_clone = archetype.clone();
+    let rc_clone = render_context.clone();
     engine.register_fn(
         "execute",
         move |call: NativeCallContext, program: &str, settings: Map| {
-            execute_with_settings(call, archetect_clone.clone(), archetype_clone.clone(), program, settings)
+            execute_with_settings(call, archetect_clone.clone(), archetype_clone.clone(), &rc_clone, program, settings)
         },
     );
     let archetect_clone = archetect.clone();
     let archetype_clone = archetype.clone();
+    let rc_clone = render_context.clone();
     engine.register_fn("capture", move |call: NativeCallContext, program: &str| {
-        capture(call, archetect_clone.clone(), archetype_clone.clone(), program)
+        capture(call, archetect_clone.clone(), archetype_clone.clone(), &rc_clone, program)
     });
     let archetect_clone = archetect.clone();
     let archetype_clone = archetype.clone();
+    let rc_clone = render_context.clone();
     engine.register_fn(
         "capture",
         move |call: NativeCallContext, program: &str, settings: Map| {
-            capture_with_settings(call, archetect_clone.clone(), archetype_clone.clone(), program, settings)
+            capture_with_settings(call, archetect_clone.clone(), archetype_clone.clone(), &rc_clone, program, settings)
         },
     );
 }
@@ -40,20 +45,27 @@ fn execute(
     call: NativeCallContext,
     archetect: Archetect,
     archetype: Archetype,
+    render_context: &RenderContext,
     program: &str,
 ) -> Result<(), Box<EvalAltResult>> {
-    execute_with_settings(call, archetect, archetype, program, Map::new())
+    execute_with_settings(call, archetect, archetype, render_context, program, Map::new())
 }
 
 fn execute_with_settings(
     _call: NativeCallContext,
     _archetect: Archetect,
     _archetype: Archetype,
+    render_context: &RenderContext,
     program: &str,
     settings: Map,
 ) -> Result<(), Box<EvalAltResult>> {
     let mut command = create_command(program, settings);
 
+    if render_context.dry_run() {
+        info!("Dry Run: skipping {:?}", command);
+        return Ok(());
+    }
+
     match command.status() {
         Ok(exit) => {
             if let Some(code) = exit.code() {
@@ -74,19 +86,29 @@ fn capture(
     call: NativeCallContext,
     archetect: Archetect,
     archetype: Archetype,
+    render_context: &RenderContext,
     program: &str,
 ) -> Result<Dynamic, Box<EvalAltResult>> {
-    capture_with_settings(call, archetect, archetype, program, Map::new())
+    capture_with_settings(call, archetect, archetype, render_context, program, Map::new())
 }
 
+/// Runs `program`, returning what it wrote to stdout.  Like `execute`, nothing is run in a dry run, where an empty
+/// string is returned in its place.
 fn capture_with_settings(
     _call: NativeCallContext,
     archetect: Archetect,
     archetype: Archetype,
+    render_context: &RenderContext,
     program: &str,
     settings: Map,
 ) -> Result<Dynamic, Box<EvalAltResult>> {
     let mut command = create_command(program, settings);
+
+    if render_context.dry_run() {
+        info!("Dry Run: skipping {:?}", command);
+        return Ok("".into());
+    }
+
     match allow_exec(&archetect, &archetype, &command) {
         Ok(allow) => {
             if allow {
@@ -0,0 +1,122 @@
+use camino::Utf8PathBuf;
+use rhai::plugin::*;
+use rhai::{Engine, EvalAltResult, Map, Module};
+
+use archetect_templating::Environment;
+
+use crate::archetype::archetype::{render_file, Archetype, OverwritePolicy};
+use crate::archetype::render_context::RenderContext;
+use crate::errors::{ArchetypeScriptError, ArchetypeScriptErrorWrapper};
+use crate::script::rhai::modules::directory_module::render_error;
+use crate::script::rhai::modules::path_module::Path;
+use crate::utils::restrict_path_manipulation;
+use crate::Archetect;
+
+pub(crate) fn register(
+    engine: &mut Engine,
+    environment: Environment<'static>,
+    archetect: Archetect,
+    archetype: Archetype,
+    render_context: RenderContext,
+) {
+    let mut module = Module::new();
+    module.set_native_fn("File", move |path: &str| {
+        File::new(environment.clone(), archetype.clone(), archetect.clone(), render_context.clone(), path)
+    });
+    engine.register_global_module(module.into());
+
+    engine.register_type_with_name::<File>("File");
+    engine.register_fn("render", render);
+    engine.register_fn("render", render_with_settings);
+}
+
+/// A single file within the archetype's content or templates directory, to be rendered to a destination of the
+/// script's choosing.
+#[derive(Clone)]
+pub struct File {
+    environment: Environment<'static>,
+    archetype: Archetype,
+    archetect: Archetect,
+    render_context: RenderContext,
+    path: Utf8PathBuf,
+}
+
+impl File {
+    pub fn new<T: Into<Utf8PathBuf>>(
+        environment: Environment<'static>,
+        archetype: Archetype,
+        archetect: Archetect,
+        render_context: RenderContext,
+        path: T,
+    ) -> Result<File, Box<EvalAltResult>> {
+        Ok(File {
+            environment,
+            archetect,
+            archetype,
+            render_context,
+            path: path.into(),
+        })
+    }
+
+    /// The file within the content directory, or failing that the templates directory.
+    fn source(&self, call: &NativeCallContext) -> Result<Utf8PathBuf, Box<EvalAltResult>> {
+        let path = restrict_path_manipulation(call, self.path.as_str())?;
+        [self.archetype.content_directory(), self.archetype.template_directory()]
+            .into_iter()
+            .map(|directory| directory.join(path))
+            .find(|source| source.is_file())
+            .ok_or_else(|| {
+                let error = ArchetypeScriptError::FileNotFound { path: path.to_string() };
+                ArchetypeScriptErrorWrapper(call, error).into()
+            })
+    }
+}
+
+pub fn render(
+    call: NativeCallContext,
+    file: &mut File,
+    destination: Dynamic,
+    context: Map,
+) -> Result<(), Box<EvalAltResult>> {
+    render_with_settings(call, file, destination, context, Map::new())
+}
+
+pub fn render_with_settings(
+    call: NativeCallContext,
+    file: &mut File,
+    destination: Dynamic,
+    context: Map,
+    settings: Map,
+) -> Result<(), Box<EvalAltResult>> {
+    let source = file.source(&call)?;
+    let destination = if destination.is_string() {
+        destination.cast::<String>()
+    } else if destination.is::<Path>() {
+        let mut path = destination.cast::<Path>();
+        path.path().to_string()
+    } else {
+        let error = ArchetypeScriptError::RenderDestinationTypeError {
+            actual: destination.to_string(),
+        };
+        return Err(ArchetypeScriptErrorWrapper(&call, error).into());
+    };
+    let destination = file
+        .render_context
+        .destination()
+        .join(restrict_path_manipulation(&call, destination.as_str())?);
+    let overwrite_policy = settings
+        .get("if_exists")
+        .and_then(|v| v.clone().try_cast::<OverwritePolicy>())
+        .unwrap_or_default();
+    render_file(
+        &file.environment,
+        &file.archetect,
+        &file.archetype,
+        &file.render_context,
+        &context,
+        source,
+        destination,
+        overwrite_policy,
+    )
+    .map_err(render_error)
+}
@@ -3,6 +3,7 @@ pub(crate) mod cases_module;
 pub(crate) mod path_module;
 pub(crate) mod directory_module;
 pub(crate) mod exec_module;
+pub(crate) mod file_module;
 pub(crate) mod formats_module;
 pub(crate) mod log_module;
 pub(crate) mod prompt_module;
@@ -1,18 +1,34 @@
 use std::fmt::{Display, Formatter};
 use camino::{Utf8Path, Utf8PathBuf};
-use log::{error, warn};
-use rhai::{CustomType, Engine, EvalAltResult, FnNamespace, FuncRegistration, Module, NativeCallContext, TypeBuilder};
+use log::{error, info, warn};
+use regex::Regex;
+use rhai::{
+    CustomType, Dynamic, Engine, EvalAltResult, FnNamespace, FuncRegistration, Map, Module, NativeCallContext,
+    TypeBuilder,
+};
 
+use archetect_api::{CommandRequest, FileEventInfo};
+
+use crate::archetype::archetype::Archetype;
 use crate::archetype::render_context::RenderContext;
+use crate::archetype::render_plan::{PlannedAction, PlannedFile};
+use crate::archetype::structured_merge::{load_structured, merge_structured, StructuredFormat};
+use crate::errors::{ArchetypeScriptError, ArchetypeScriptErrorWrapper, RenderError};
 use crate::utils::restrict_path_manipulation;
+use crate::Archetect;
 
 pub(crate) fn register(
     engine: &mut Engine,
+    archetect: Archetect,
+    archetype: Archetype,
     render_context: RenderContext,
 ) {
     let mut module = Module::new();
-    let func = move |call: NativeCallContext, path: &str| {
-        create_path(&call, render_context.clone(), path.to_string())
+    // Edits are attributed to the script making them
+    let source = archetype.directory().script().unwrap_or_else(|_| archetype.root().to_path_buf());
+    let func = move |call: NativeCallContext, path: &str| -> Result<Path, Box<EvalAltResult>> {
+        let path = create_path(&call, render_context.clone(), path.to_string())?;
+        Ok(path.with_editor(archetect.clone(), source.clone()))
     };
     FuncRegistration::new("Path")
         .with_namespace(FnNamespace::Internal)
@@ -31,7 +47,9 @@ pub fn create_path(call: &NativeCallContext, render_context: RenderContext, path
 #[derive(Clone, Debug)]
 pub struct Path {
     path: String,
-    full_path: Utf8PathBuf
+    full_path: Utf8PathBuf,
+    render_context: RenderContext,
+    editor: Option<(Archetect, Utf8PathBuf)>,
 }
 
 impl Path {
@@ -39,9 +57,17 @@ impl Path {
         Path {
             path: path.clone(),
             full_path: render_context.destination().join(&path),
+            render_context,
+            editor: None,
         }
     }
 
+    /// Reports edits to this file as made by the script at `source`, through `archetect`.
+    pub fn with_editor(mut self, archetect: Archetect, source: Utf8PathBuf) -> Path {
+        self.editor = Some((archetect, source));
+        self
+    }
+
     pub fn path(&mut self) -> &str {
         &self.path
     }
@@ -51,39 +77,311 @@ impl Path {
     }
 
     pub fn exists(&mut self) -> bool {
-        self.full_path.exists()
+        self.render_context.output_exists(&self.full_path)
     }
 
     //noinspection RsSelfConvention
     pub fn is_file(&mut self) -> bool {
-        self.full_path.is_file()
+        self.render_context.output_is_file(&self.full_path)
     }
 
     //noinspection RsSelfConvention
     pub fn is_dir(&mut self) -> bool {
-        self.full_path.is_dir()
+        self.render_context.output_is_dir(&self.full_path)
     }
 
     pub fn remove(&mut self) {
-        if self.full_path.is_file() {
-            match std::fs::remove_file(&self.full_path) {
-                Ok(_) => {}
-                Err(err) => {
-                    error!("Error deleting file {}: {}", self.full_path.to_string(), err);
-                }
-            }
-        } else if self.full_path.is_dir() {
-            match std::fs::remove_dir_all(&self.full_path) {
-                Ok(_) => {}
-                Err(err) => {
-                    error!("Error deleting directory {}: {}", self.full_path.to_string(), err);
-                }
-            }
-        } else {
+        if self.render_context.dry_run() {
+            info!("Dry Run: skipping removal of '{}'", self.path);
+        } else if !self.exists() {
             warn!("Attempting to delete path '{}', but it does not exist", self.path);
+        } else if let Err(err) = self.render_context.remove_output(&self.full_path) {
+            error!("Error deleting {}: {}", self.full_path, err);
+        }
+    }
+
+    fn read(&mut self) -> Result<String, ArchetypeScriptError> {
+        if !self.is_file() {
+            return Err(ArchetypeScriptError::EditTargetNotFound { path: self.path.clone() });
+        }
+        let contents = self
+            .render_context
+            .read_existing(&self.full_path)
+            .map_err(|err| ArchetypeScriptError::EditError { path: self.path.clone(), message: err.to_string() })?;
+        String::from_utf8(contents)
+            .map_err(|err| ArchetypeScriptError::EditError { path: self.path.clone(), message: err.to_string() })
+    }
+
+    /// Applies `edit` to the contents of this file, writing the result back if it changed anything.  Returns whether
+    /// the file was, or in a dry run would have been, changed.  With `create`, a missing file is edited as if empty.
+    /// Changes are recorded in the render plan, and reported as rendered files, like those of rendered directories.
+    fn edit<F>(&mut self, create: bool, edit: F) -> Result<bool, ArchetypeScriptError>
+    where
+        F: FnOnce(&str) -> Result<Option<String>, ArchetypeScriptError>,
+    {
+        let exists = self.exists();
+        let contents = if create && !exists { String::new() } else { self.read()? };
+
+        let Some(edited) = edit(&contents)? else {
+            return Ok(false);
+        };
+
+        let action = if exists { PlannedAction::Edit } else { PlannedAction::Create };
+        let source = match &self.editor {
+            Some((_, source)) => source.clone(),
+            None => Utf8PathBuf::new(),
+        };
+        self.render_context
+            .plan()
+            .record(PlannedFile::new(&self.full_path, source, action));
+
+        if self.render_context.dry_run() {
+            info!("Dry Run: skipping edit of '{}'", self.path);
+            return Ok(true);
+        }
+        let error = |err: RenderError| ArchetypeScriptError::EditError {
+            path: self.path.clone(),
+            message: err.to_string(),
+        };
+        if let Some(parent) = self.full_path.parent() {
+            self.render_context.create_output_dir(parent).map_err(error)?;
+        }
+        self.render_context.write_output(&self.full_path, edited.as_bytes()).map_err(error)?;
+        if let Some((archetect, source)) = &self.editor {
+            archetect.request(CommandRequest::FileRendered(FileEventInfo::new(
+                self.full_path.as_str(),
+                source.as_str(),
+            )));
+        }
+        Ok(true)
+    }
+
+    pub fn insert_before(&mut self, anchor: &str, snippet: &str, settings: &Map) -> Result<bool, ArchetypeScriptError> {
+        let anchor = Anchor::new(anchor, settings)?;
+        let path = self.path.clone();
+        self.edit(false, |contents| insert(contents, &path, &anchor, snippet, false))
+    }
+
+    pub fn insert_after(&mut self, anchor: &str, snippet: &str, settings: &Map) -> Result<bool, ArchetypeScriptError> {
+        let anchor = Anchor::new(anchor, settings)?;
+        let path = self.path.clone();
+        self.edit(false, |contents| insert(contents, &path, &anchor, snippet, true))
+    }
+
+    pub fn append_if_absent(&mut self, snippet: &str) -> Result<bool, ArchetypeScriptError> {
+        self.edit(false, |contents| Ok(append_if_absent(contents, snippet)))
+    }
+
+    pub fn replace_between(
+        &mut self,
+        start: &str,
+        end: &str,
+        snippet: &str,
+        settings: &Map,
+    ) -> Result<bool, ArchetypeScriptError> {
+        let start = Anchor::new(start, settings)?;
+        let end = Anchor::new(end, settings)?;
+        let path = self.path.clone();
+        self.edit(false, |contents| replace_between(contents, &path, &start, &end, snippet))
+    }
+
+    /// The structured format of this file, given by the `format` setting or implied by its extension.
+    fn format(&self, settings: &Map) -> Result<StructuredFormat, ArchetypeScriptError> {
+        let format = match settings.get("format") {
+            Some(format) => StructuredFormat::from_name(&format.to_string()),
+            None => StructuredFormat::from_path(&self.path),
+        };
+        format.ok_or_else(|| ArchetypeScriptError::EditFormatUnknown { path: self.path.clone() })
+    }
+
+    pub fn load(&mut self, settings: &Map) -> Result<Dynamic, ArchetypeScriptError> {
+        let format = self.format(settings)?;
+        let contents = self.read()?;
+        let value = load_structured(&self.path, format, &contents)?;
+        rhai::serde::to_dynamic(value)
+            .map_err(|err| ArchetypeScriptError::EditError { path: self.path.clone(), message: err.to_string() })
+    }
+
+    /// Deep-merges `patch` into this JSON, YAML, or TOML file, creating it if it does not yet exist.
+    pub fn merge(&mut self, patch: Map, settings: &Map) -> Result<bool, ArchetypeScriptError> {
+        let format = self.format(settings)?;
+        let patch: serde_json::Value = rhai::serde::from_dynamic(&patch.into())
+            .map_err(|err| ArchetypeScriptError::EditError { path: self.path.clone(), message: err.to_string() })?;
+        let path = self.path.clone();
+        self.edit(true, |contents| merge_structured(&path, format, contents, &patch))
+    }
+}
+
+/// Identifies the line of a file to edit at, either by a marker it contains, such as a comment, or by a regular
+/// expression it matches when the `regex` setting is enabled.
+enum Anchor {
+    Marker(String),
+    Pattern(Regex),
+}
+
+impl Anchor {
+    fn new(anchor: &str, settings: &Map) -> Result<Anchor, ArchetypeScriptError> {
+        let regex = settings
+            .get("regex")
+            .and_then(|value| value.as_bool().ok())
+            .unwrap_or_default();
+        if regex {
+            Regex::new(anchor).map(Anchor::Pattern).map_err(|err| ArchetypeScriptError::EditAnchorInvalid {
+                anchor: anchor.to_owned(),
+                message: err.to_string(),
+            })
+        } else {
+            Ok(Anchor::Marker(anchor.to_owned()))
+        }
+    }
+
+    fn matches(&self, line: &str) -> bool {
+        match self {
+            Anchor::Marker(marker) => line.contains(marker.as_str()),
+            Anchor::Pattern(pattern) => pattern.is_match(line),
+        }
+    }
+
+    /// The byte range of the first line at or after `from` matching this anchor, including its line ending.
+    fn find_line(&self, contents: &str, from: usize) -> Option<(usize, usize)> {
+        let mut start = from;
+        for line in contents[from..].split_inclusive('\n') {
+            let end = start + line.len();
+            if self.matches(line.trim_end_matches(['\r', '\n'])) {
+                return Some((start, end));
+            }
+            start = end;
+        }
+        None
+    }
+}
+
+impl Display for Anchor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Anchor::Marker(marker) => write!(f, "{}", marker),
+            Anchor::Pattern(pattern) => write!(f, "{}", pattern),
+        }
+    }
+}
+
+/// The line ending used by `contents`, so that inserted snippets match the file they are inserted into.
+fn line_ending(contents: &str) -> &'static str {
+    if contents.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    }
+}
+
+/// `snippet` as whole lines, ending with `line_ending`.
+fn snippet_lines(snippet: &str, line_ending: &str) -> String {
+    let mut lines = snippet
+        .lines()
+        .collect::<Vec<&str>>()
+        .join(line_ending);
+    lines.push_str(line_ending);
+    lines
+}
+
+/// The lines of `text`, without line endings or trailing whitespace, for comparing snippets with the lines of a file.
+fn normalized_lines(text: &str) -> Vec<&str> {
+    text.lines().map(str::trim_end).collect()
+}
+
+fn insert(
+    contents: &str,
+    path: &str,
+    anchor: &Anchor,
+    snippet: &str,
+    after: bool,
+) -> Result<Option<String>, ArchetypeScriptError> {
+    let (start, end) = anchor.find_line(contents, 0).ok_or_else(|| ArchetypeScriptError::EditAnchorNotFound {
+        path: path.to_owned(),
+        anchor: anchor.to_string(),
+    })?;
+
+    // The snippet has already been inserted if the lines beside the anchor hold it, wherever else it appears
+    let lines = normalized_lines(snippet);
+    if lines.is_empty() {
+        return Ok(None);
+    }
+    let adjacent = if after {
+        normalized_lines(&contents[end..]).into_iter().take(lines.len()).collect()
+    } else {
+        let preceding = normalized_lines(&contents[..start]);
+        preceding[preceding.len().saturating_sub(lines.len())..].to_vec()
+    };
+    if adjacent == lines {
+        return Ok(None);
+    }
+
+    let line_ending = line_ending(contents);
+    let mut edited = String::with_capacity(contents.len() + snippet.len() + 2);
+    if after {
+        edited.push_str(&contents[..end]);
+        if !contents[..end].ends_with('\n') {
+            edited.push_str(line_ending);
         }
+        edited.push_str(&snippet_lines(snippet, line_ending));
+        edited.push_str(&contents[end..]);
+    } else {
+        edited.push_str(&contents[..start]);
+        edited.push_str(&snippet_lines(snippet, line_ending));
+        edited.push_str(&contents[start..]);
     }
+    Ok(Some(edited))
+}
 
+/// Appends `snippet` unless its lines already appear together, as whole lines, somewhere in `contents`.
+fn append_if_absent(contents: &str, snippet: &str) -> Option<String> {
+    let lines = normalized_lines(snippet);
+    if lines.is_empty() || normalized_lines(contents).windows(lines.len()).any(|window| window == lines) {
+        return None;
+    }
+    let line_ending = line_ending(contents);
+    let mut edited = contents.to_owned();
+    if !edited.is_empty() && !edited.ends_with('\n') {
+        edited.push_str(line_ending);
+    }
+    edited.push_str(&snippet_lines(snippet, line_ending));
+    Some(edited)
+}
+
+/// Replaces every line between the lines matching `start` and `end`, leaving both marker lines in place.
+fn replace_between(
+    contents: &str,
+    path: &str,
+    start: &Anchor,
+    end: &Anchor,
+    snippet: &str,
+) -> Result<Option<String>, ArchetypeScriptError> {
+    let not_found = |anchor: &Anchor| ArchetypeScriptError::EditAnchorNotFound {
+        path: path.to_owned(),
+        anchor: anchor.to_string(),
+    };
+    let (_, region_start) = start.find_line(contents, 0).ok_or_else(|| not_found(start))?;
+    let (region_end, _) = end.find_line(contents, region_start).ok_or_else(|| not_found(end))?;
+
+    let line_ending = line_ending(contents);
+    let mut edited = contents[..region_start].to_owned();
+    if !edited.ends_with('\n') {
+        edited.push_str(line_ending);
+    }
+    if !snippet.is_empty() {
+        edited.push_str(&snippet_lines(snippet, line_ending));
+    }
+    edited.push_str(&contents[region_end..]);
+
+    if edited == contents {
+        Ok(None)
+    } else {
+        Ok(Some(edited))
+    }
+}
+
+fn edit_result<T>(call: &NativeCallContext, result: Result<T, ArchetypeScriptError>) -> Result<T, Box<EvalAltResult>> {
+    result.map_err(|err| ArchetypeScriptErrorWrapper(call, err).into())
 }
 
 impl CustomType for Path {
@@ -95,6 +393,51 @@ impl CustomType for Path {
              .with_fn("is_dir", Path::is_dir)
              .with_fn("delete", Path::remove)
              .with_fn("remove", Path::remove)
+             .with_fn("insert_before", |call: NativeCallContext, path: &mut Path, anchor: &str, snippet: &str| {
+                 edit_result(&call, path.insert_before(anchor, snippet, &Map::new()))
+             })
+             .with_fn(
+                 "insert_before",
+                 |call: NativeCallContext, path: &mut Path, anchor: &str, snippet: &str, settings: Map| {
+                     edit_result(&call, path.insert_before(anchor, snippet, &settings))
+                 },
+             )
+             .with_fn("insert_after", |call: NativeCallContext, path: &mut Path, anchor: &str, snippet: &str| {
+                 edit_result(&call, path.insert_after(anchor, snippet, &Map::new()))
+             })
+             .with_fn(
+                 "insert_after",
+                 |call: NativeCallContext, path: &mut Path, anchor: &str, snippet: &str, settings: Map| {
+                     edit_result(&call, path.insert_after(anchor, snippet, &settings))
+                 },
+             )
+             .with_fn("append_if_absent", |call: NativeCallContext, path: &mut Path, snippet: &str| {
+                 edit_result(&call, path.append_if_absent(snippet))
+             })
+             .with_fn(
+                 "replace_between",
+                 |call: NativeCallContext, path: &mut Path, start: &str, end: &str, snippet: &str| {
+                     edit_result(&call, path.replace_between(start, end, snippet, &Map::new()))
+                 },
+             )
+             .with_fn(
+                 "replace_between",
+                 |call: NativeCallContext, path: &mut Path, start: &str, end: &str, snippet: &str, settings: Map| {
+                     edit_result(&call, path.replace_between(start, end, snippet, &settings))
+                 },
+             )
+             .with_fn("load", |call: NativeCallContext, path: &mut Path| {
+                 edit_result(&call, path.load(&Map::new()))
+             })
+             .with_fn("load", |call: NativeCallContext, path: &mut Path, settings: Map| {
+                 edit_result(&call, path.load(&settings))
+             })
+             .with_fn("merge", |call: NativeCallContext, path: &mut Path, patch: Map| {
+                 edit_result(&call, path.merge(patch, &Map::new()))
+             })
+             .with_fn("merge", |call: NativeCallContext, path: &mut Path, patch: Map, settings: Map| {
+                 edit_result(&call, path.merge(patch, &settings))
+             })
              .with_fn("path", |destination: &mut Path| destination.path().to_string())
              .with_fn("full_path", |destination: &mut Path| destination.full_path().to_string())
              .with_fn("to_debug", |destination: &mut Path| destination.to_string())
@@ -107,4 +450,63 @@ impl Display for Path {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "Path(\"{}\")", self.path)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marker(marker: &str) -> Anchor {
+        Anchor::new(marker, &Map::new()).unwrap()
+    }
+
+    #[test]
+    fn test_insert() {
+        let contents = "mod a;\n// archetect:modules\nfn main() {}\n";
+        let after = insert(contents, "lib.rs", &marker("archetect:modules"), "mod b;", true).unwrap();
+        assert_eq!(after.as_deref(), Some("mod a;\n// archetect:modules\nmod b;\nfn main() {}\n"));
+        let before = insert(contents, "lib.rs", &marker("fn main"), "mod b;\n", false).unwrap();
+        assert_eq!(before.as_deref(), Some("mod a;\n// archetect:modules\nmod b;\nfn main() {}\n"));
+        assert!(insert(contents, "lib.rs", &marker("missing"), "mod b;", true).is_err());
+    }
+
+    #[test]
+    fn test_insert_checked_at_anchor() {
+        let contents = "mod a;\n// archetect:modules\nmod b;\nfn main() {}\n";
+        assert!(insert(contents, "lib.rs", &marker("archetect:modules"), "mod b;", true).unwrap().is_none());
+        assert!(insert(contents, "lib.rs", &marker("fn main"), "mod b;  \r\n", false).unwrap().is_none());
+        // Appearing elsewhere in the file does not count as having been inserted at the anchor
+        let edited = insert(contents, "lib.rs", &marker("archetect:modules"), "mod a;", true).unwrap();
+        assert_eq!(edited.as_deref(), Some("mod a;\n// archetect:modules\nmod a;\nmod b;\nfn main() {}\n"));
+        let edited = insert(contents, "lib.rs", &marker("fn main"), "mod a;", false).unwrap();
+        assert_eq!(edited.as_deref(), Some("mod a;\n// archetect:modules\nmod b;\nmod a;\nfn main() {}\n"));
+    }
+
+    #[test]
+    fn test_insert_regex() {
+        let mut settings = Map::new();
+        settings.insert("regex".into(), true.into());
+        let anchor = Anchor::new(r"^\[dependencies\]$", &settings).unwrap();
+        let edited = insert("[package]\r\n[dependencies]", "Cargo.toml", &anchor, "serde = \"1\"", true).unwrap();
+        assert_eq!(edited.as_deref(), Some("[package]\r\n[dependencies]\r\nserde = \"1\"\r\n"));
+        assert!(Anchor::new("(", &settings).is_err());
+    }
+
+    #[test]
+    fn test_append_if_absent() {
+        assert_eq!(append_if_absent("target/", "*.log").as_deref(), Some("target/\n*.log\n"));
+        assert!(append_if_absent("target/\n*.log\n", "*.log\n").is_none());
+        // Only whole lines count, rather than any text containing the snippet
+        assert_eq!(append_if_absent("*.log.bak\n", "*.log").as_deref(), Some("*.log.bak\n*.log\n"));
+        assert_eq!(append_if_absent("# *.log\n", "*.log").as_deref(), Some("# *.log\n*.log\n"));
+    }
+
+    #[test]
+    fn test_replace_between() {
+        let contents = "# begin\nold\n# end\n";
+        let edited = replace_between(contents, "README.md", &marker("# begin"), &marker("# end"), "new").unwrap();
+        assert_eq!(edited.as_deref(), Some("# begin\nnew\n# end\n"));
+        let again = replace_between("# begin\nnew\n# end\n", "README.md", &marker("# begin"), &marker("# end"), "new");
+        assert!(again.unwrap().is_none());
+        assert!(replace_between("# end\n# begin\n", "README.md", &marker("# begin"), &marker("# end"), "").is_err());
+    }
+}
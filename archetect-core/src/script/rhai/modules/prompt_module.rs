@@ -79,116 +79,55 @@ fn prompt_to_map<'a, K: AsRef<str>>(
         )
     })?;
 
-    return match prompt_type {
+    let value = match prompt_type {
         PromptType::Text => {
-            let value = text::prompt(call, message, &settings, &archetect, &render_context, Some(key), answer)?;
-            match value {
-                None => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::Opaque(Dynamic::UNIT));
-                    Ok(results.into())
-                }
-                Some(value) => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::String(value));
-                    Ok(results.into())
-                }
-            }
-        }
-        PromptType::Bool => {
-            let value = bool::prompt(call, message, &archetect, &render_context, &settings, Some(key), answer)?;
-            match value {
-                None => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::Opaque(Dynamic::UNIT));
-                    Ok(results.into())
-                }
-                Some(value) => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::Opaque(value.into()));
-                    Ok(results.into())
-                }
-            }
-        }
-        PromptType::Int => {
-            let value = int::prompt_int(call, message, &archetect, &render_context, &settings, Some(key), answer)?;
-            match value {
-                None => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::Opaque(Dynamic::UNIT));
-                    Ok(results.into())
-                }
-                Some(value) => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::Opaque(value.into()));
-                    Ok(results.into())
-                }
-            }
-        }
-        PromptType::Select(options) => {
-            let value = select::prompt(
-                call,
-                message,
-                options,
-                &archetect,
-                &render_context,
-                &settings,
-                Some(key),
-                answer,
-            )?;
-            match value {
-                None => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::Opaque(Dynamic::UNIT));
-                    Ok(results.into())
-                }
-                Some(value) => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::String(value));
-                    Ok(results.into())
-                }
-            }
-        }
-        PromptType::MultiSelect(options) => {
-            let value = multiselect::prompt(
-                call,
-                message,
-                options,
-                &archetect,
-                &render_context,
-                &settings,
-                Some(key),
-                answer,
-            )?;
-            match value {
-                None => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::Opaque(Dynamic::UNIT));
-                    Ok(results.into())
-                }
-                Some(list) => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::List(list));
-                    Ok(results.into())
-                }
-            }
+            text::prompt(call, message, &settings, &archetect, &render_context, Some(key), answer)?.map(Caseable::String)
         }
+        PromptType::Bool => bool::prompt(call, message, &archetect, &render_context, &settings, Some(key), answer)?
+            .map(|value| Caseable::Opaque(value.into())),
+        PromptType::Int => int::prompt_int(call, message, &archetect, &render_context, &settings, Some(key), answer)?
+            .map(|value| Caseable::Opaque(value.into())),
+        PromptType::Select(options) => select::prompt(
+            call,
+            message,
+            options,
+            &archetect,
+            &render_context,
+            &settings,
+            Some(key),
+            answer,
+        )?
+        .map(Caseable::String),
+        PromptType::MultiSelect(options) => multiselect::prompt(
+            call,
+            message,
+            options,
+            &archetect,
+            &render_context,
+            &settings,
+            Some(key),
+            answer,
+        )?
+        .map(Caseable::List),
         PromptType::Editor => {
-            let value = editor::prompt(call, message, &settings, &archetect, &render_context, Some(key), answer)?;
-            match value {
-                None => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::Opaque(Dynamic::UNIT));
-                    Ok(results.into())
-                }
-                Some(value) => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::String(value));
-                    Ok(results.into())
-                }
-            }
+            editor::prompt(call, message, &settings, &archetect, &render_context, Some(key), answer)?.map(Caseable::String)
         }
         PromptType::List => {
-            match list::prompt(call, message, &archetect, &render_context, &settings, Some(key), answer)? {
-                None => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::Opaque(Dynamic::UNIT));
-                    Ok(results.into())
-                }
-                Some(list) => {
-                    expand_key_value_cases(&casing, &mut results, key, Caseable::List(list));
-                    Ok(results.into())
-                }
-            }
+            list::prompt(call, message, &archetect, &render_context, &settings, Some(key), answer)?.map(Caseable::List)
+        }
+    };
+
+    // Skipped optional prompts are left out of the log, so that replays prompt for them again rather than answering
+    // them with nothing
+    let value = match value {
+        Some(value) => {
+            render_context.answer_log().record(key, apply_case(value.clone(), None));
+            value
         }
+        None => Caseable::Opaque(Dynamic::UNIT),
     };
+    expand_key_value_cases(&casing, &mut results, key, value);
+    Ok(results.into())
 }
 
 fn prompt_to_value(
@@ -214,113 +153,88 @@ fn prompt_to_value(
         None
     };
 
-    match prompt_type {
-        PromptType::Text => {
-            let value = text::prompt(
-                call,
-                message,
-                &settings,
-                &archetect,
-                &render_context,
-                answer_key.as_ref(),
-                answer,
-            )?;
-            match value {
-                None => Ok(Dynamic::UNIT),
-                Some(list) => Ok(apply_case(Caseable::String(list), case)),
-            }
-        }
-        PromptType::Bool => {
-            let value = bool::prompt(
-                call,
-                message,
-                &archetect,
-                &render_context,
-                &settings,
-                answer_key,
-                answer,
-            )?;
-            match value {
-                None => Ok(Dynamic::UNIT),
-                Some(value) => Ok(value.into()),
-            }
-        }
-        PromptType::Int => {
-            let value = int::prompt_int(
-                call,
-                message,
-                &archetect,
-                &render_context,
-                &settings,
-                answer_key,
-                answer,
-            )?;
-            match value {
-                None => Ok(Dynamic::UNIT),
-                Some(value) => Ok(value.into()),
-            }
-        }
-        PromptType::Select(options) => {
-            let value = select::prompt(
-                call,
-                message,
-                options,
-                &archetect,
-                &render_context,
-                &settings,
-                answer_key,
-                answer,
-            )?;
-            match value {
-                Some(value) => Ok(apply_case(Caseable::String(value), case)),
-                None => Ok(Dynamic::UNIT),
-            }
-        }
-        PromptType::MultiSelect(options) => {
-            let value = multiselect::prompt(
-                call,
-                message,
-                options,
-                &archetect,
-                &render_context,
-                &settings,
-                answer_key,
-                answer,
-            )?;
-            match value {
-                Some(value) => Ok(apply_case(Caseable::List(value), case)),
-                None => Ok(Dynamic::UNIT),
-            }
-        }
-        PromptType::Editor => {
-            let value = editor::prompt(
-                call,
-                message,
-                &settings,
-                &archetect,
-                &render_context,
-                answer_key,
-                answer,
-            )?;
-            match value {
-                None => Ok(Dynamic::UNIT),
-                Some(list) => Ok(apply_case(Caseable::String(list), case)),
-            }
-        }
-        PromptType::List => {
-            let value = list::prompt(
-                call,
-                message,
-                &archetect,
-                &render_context,
-                &settings,
-                answer_key,
-                answer,
-            )?;
-            match value {
-                None => Ok(Dynamic::UNIT),
-                Some(list) => Ok(apply_case(Caseable::List(list), case)),
+    let value = match prompt_type {
+        PromptType::Text => text::prompt(
+            call,
+            message,
+            &settings,
+            &archetect,
+            &render_context,
+            answer_key.as_ref(),
+            answer,
+        )?
+        .map(Caseable::String),
+        PromptType::Bool => bool::prompt(
+            call,
+            message,
+            &archetect,
+            &render_context,
+            &settings,
+            answer_key.as_ref(),
+            answer,
+        )?
+        .map(|value| Caseable::Opaque(value.into())),
+        PromptType::Int => int::prompt_int(
+            call,
+            message,
+            &archetect,
+            &render_context,
+            &settings,
+            answer_key.as_ref(),
+            answer,
+        )?
+        .map(|value| Caseable::Opaque(value.into())),
+        PromptType::Select(options) => select::prompt(
+            call,
+            message,
+            options,
+            &archetect,
+            &render_context,
+            &settings,
+            answer_key.as_ref(),
+            answer,
+        )?
+        .map(Caseable::String),
+        PromptType::MultiSelect(options) => multiselect::prompt(
+            call,
+            message,
+            options,
+            &archetect,
+            &render_context,
+            &settings,
+            answer_key.as_ref(),
+            answer,
+        )?
+        .map(Caseable::List),
+        PromptType::Editor => editor::prompt(
+            call,
+            message,
+            &settings,
+            &archetect,
+            &render_context,
+            answer_key.as_ref(),
+            answer,
+        )?
+        .map(Caseable::String),
+        PromptType::List => list::prompt(
+            call,
+            message,
+            &archetect,
+            &render_context,
+            &settings,
+            answer_key.as_ref(),
+            answer,
+        )?
+        .map(Caseable::List),
+    };
+
+    match value {
+        None => Ok(Dynamic::UNIT),
+        Some(value) => {
+            if let Some(key) = &answer_key {
+                render_context.answer_log().record(key, apply_case(value.clone(), None));
             }
+            Ok(apply_case(value, case))
         }
     }
 }
@@ -541,6 +455,7 @@ impl RequirementDescription for Map {
     }
 }
 
+#[derive(Clone)]
 pub enum Caseable {
     String(String),
     List(Vec<String>),
@@ -31,6 +31,7 @@ fn set(call: &NativeCallContext, render_context: RenderContext, key: &str, mut v
         if let Some(answer) = answers.get(key) {
             value = answer.clone();
         }
+        render_context.answer_log().record(key, value.clone());
     }
 
     let case_strategies = extract_case_strategies(&settings).map_err(|err| {
@@ -1,11 +1,11 @@
 use std::collections::HashSet;
 use std::fs;
-use std::process::Command;
 use std::sync::{Mutex, OnceLock};
 
 use camino::{Utf8Path, Utf8PathBuf};
 use chrono::TimeZone;
-use git2::Repository;
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::{AutotagOption, Commit, Cred, CredentialType, FetchOptions, RemoteCallbacks, Repository};
 use log::{debug, info, trace, warn};
 use regex::Regex;
 use url::Url;
@@ -65,6 +65,26 @@ impl Source {
         &self.source_type
     }
 
+    /// The git reference requested for a remote source, if any.
+    pub fn gitref(&self) -> Option<String> {
+        match &self.source_type {
+            SourceType::RemoteGit { gitref, .. } => gitref.clone(),
+            _ => None,
+        }
+    }
+
+    /// The commit currently checked out for a remote source.
+    pub fn commit(&self) -> Option<String> {
+        match &self.source_type {
+            SourceType::RemoteGit { cache_path, .. } => {
+                let repo = Repository::open(cache_path).ok()?;
+                let commit = repo.head().ok()?.peel_to_commit().ok()?;
+                Some(commit.id().to_string())
+            }
+            _ => None,
+        }
+    }
+
     pub fn source_contents(&self) -> SourceContents {
         if self.source_type().directory().join("catalog.yaml").is_file() ||
             self.source_type().directory().join("catalog.yml").is_file() {
@@ -315,94 +335,271 @@ fn cache_git_repo(
     cache_destination: &Utf8Path,
     force_pull: bool,
 ) -> Result<(), SourceError> {
-    if !cache_destination.exists() {
-        if !archetect.is_offline() {
-            if cached_paths().lock().unwrap().insert(url.to_owned()) {
-                info!("Cloning {}", url);
-                debug!("Cloning to {}", cache_destination.as_str());
-                handle_git(Command::new("git").args(["clone", url, cache_destination.as_str(), "-q"]))?;
-                let repo = git2::Repository::open(cache_destination.join(".git"))?;
-                write_timestamp(&repo)?;
-            }
-        } else {
+    let repo = if !cache_destination.exists() {
+        if archetect.is_offline() {
             return Err(SourceError::OfflineAndNotCached(url.to_owned()));
         }
+        cached_paths().lock().unwrap().insert(url.to_owned());
+        info!("Cloning {}", url);
+        debug!("Cloning to {}", cache_destination.as_str());
+        let repo = RepoBuilder::new()
+            .fetch_options(fetch_options())
+            .clone(url, cache_destination.as_std_path())
+            .map_err(|source| SourceError::CloneError { url: url.to_owned(), source })?;
+        write_timestamp(&repo)?;
+        repo
     } else {
         let repo = Repository::open(cache_destination.join(".git"))?;
         if force_pull || should_pull(&repo, &archetect)? {
             if cached_paths().lock().unwrap().insert(url.to_owned()) {
                 info!("Fetching {}", url);
-                handle_git(Command::new("git").current_dir(cache_destination).args(["fetch", "-q"]))?;
+                fetch(&repo).map_err(|source| SourceError::FetchError { url: url.to_owned(), source })?;
                 write_timestamp(&repo)?;
             }
         } else {
             trace!("Using cache for {}", url);
         }
-    }
+        repo
+    };
 
     let gitref = if let Some(gitref) = gitref {
         gitref.to_owned()
     } else {
-        find_default_branch(cache_destination.as_str())?
+        find_default_branch(&repo)?
     };
 
-    let gitref_spec = if is_branch(cache_destination.as_str(), &gitref) {
-        format!("origin/{}", &gitref)
-    } else {
-        gitref
-    };
+    let commit = resolve_gitref(&repo, &gitref).ok_or_else(|| SourceError::GitRefNotFound {
+        url: url.to_owned(),
+        gitref: gitref.clone(),
+    })?;
 
-    debug!("Checking out {}", gitref_spec);
-    handle_git(
-        Command::new("git")
-            .current_dir(cache_destination)
-            .args(["checkout", &gitref_spec]),
-    )?;
+    debug!("Checking out {} ({})", gitref, commit.id());
+    checkout(&repo, &commit).map_err(|source| SourceError::CheckoutError {
+        url: url.to_owned(),
+        gitref,
+        source,
+    })?;
 
     Ok(())
 }
 
-fn is_branch(path: &str, gitref: &str) -> bool {
-    handle_git(
-        Command::new("git")
-            .current_dir(path)
-            .arg("show-ref")
-            .arg("-q")
-            .arg("--verify")
-            .arg(format!("refs/remotes/origin/{}", gitref)),
-    )
-    .is_ok()
+/// Fetches every branch and tag from the repository's `origin` remote.
+fn fetch(repo: &Repository) -> Result<(), git2::Error> {
+    let mut remote = repo.find_remote("origin")?;
+    let mut options = fetch_options();
+    options.download_tags(AutotagOption::All);
+    remote.fetch(&[] as &[&str], Some(&mut options), None)
 }
 
-fn find_default_branch(path: &str) -> Result<String, SourceError> {
-    for candidate in &["main", "master"] {
-        if is_branch(path, candidate) {
-            return Ok((*candidate).to_owned());
+/// Options for contacting remotes, authenticating with the credentials offered by [Credentials].
+fn fetch_options() -> FetchOptions<'static> {
+    let mut credentials = Credentials::new(default_ssh_directory());
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| credentials.next(url, username, allowed));
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(callbacks);
+    options
+}
+
+/// The private keys ssh looks for by default, in the order it tries them.
+const DEFAULT_SSH_KEYS: &[&str] = &["id_rsa", "id_ecdsa", "id_ed25519"];
+
+fn default_ssh_directory() -> Option<Utf8PathBuf> {
+    let home = directories::UserDirs::new()?.home_dir().join(".ssh");
+    Utf8PathBuf::from_path_buf(home).ok()
+}
+
+type CredentialFn<'a> = Box<dyn Fn() -> Result<Cred, git2::Error> + 'a>;
+
+/// Offers a different credential each time a remote asks for one, as credentials are asked for again whenever those
+/// offered are rejected: keys held by an SSH agent, then the default private keys in `ssh_directory`, then the git
+/// credential helpers.  Once every credential allowed by the remote has been tried, authentication fails with an
+/// error naming them.
+struct Credentials {
+    ssh_directory: Option<Utf8PathBuf>,
+    tried: Vec<String>,
+}
+
+impl Credentials {
+    fn new(ssh_directory: Option<Utf8PathBuf>) -> Credentials {
+        Credentials {
+            ssh_directory,
+            tried: vec![],
         }
     }
-    Err(SourceError::NoDefaultBranch)
+
+    fn next(&mut self, url: &str, username: Option<&str>, allowed: CredentialType) -> Result<Cred, git2::Error> {
+        let username = username.unwrap_or("git");
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(username);
+        }
+
+        let mut candidates: Vec<(String, CredentialFn)> = vec![];
+        if allowed.contains(CredentialType::SSH_KEY) {
+            candidates.push(("SSH agent".to_owned(), Box::new(move || Cred::ssh_key_from_agent(username))));
+            let keys = self
+                .ssh_directory
+                .iter()
+                .flat_map(|directory| DEFAULT_SSH_KEYS.iter().map(move |key| directory.join(key)))
+                .filter(|key| key.is_file());
+            for key in keys {
+                let label = format!("SSH key {}", key);
+                candidates.push((label, Box::new(move || Cred::ssh_key(username, None, key.as_std_path(), None))));
+            }
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            candidates.push((
+                "git credential helpers".to_owned(),
+                Box::new(move || Cred::credential_helper(&git2::Config::open_default()?, url, Some(username))),
+            ));
+        }
+        if allowed.contains(CredentialType::DEFAULT) {
+            candidates.push(("default credentials".to_owned(), Box::new(Cred::default)));
+        }
+
+        for (label, credential) in candidates {
+            if self.tried.contains(&label) {
+                continue;
+            }
+            self.tried.push(label);
+            match credential() {
+                Ok(credential) => return Ok(credential),
+                Err(error) => debug!("{} unavailable for {}: {}", self.tried.last().expect("Pushed"), url, error),
+            }
+        }
+
+        let tried = if self.tried.is_empty() {
+            "no supported credential types were allowed".to_owned()
+        } else {
+            format!("tried {}", self.tried.join(", "))
+        };
+        Err(git2::Error::from_str(&format!("authentication failed for {}: {}", url, tried)))
+    }
+}
+
+/// The commit named by `gitref`, which may be a branch of the `origin` remote, a tag, or a commit.
+fn resolve_gitref<'r>(repo: &'r Repository, gitref: &str) -> Option<Commit<'r>> {
+    if let Ok(reference) = repo.find_reference(&format!("refs/remotes/origin/{}", gitref)) {
+        return reference.peel_to_commit().ok();
+    }
+    repo.revparse_single(gitref).ok()?.peel_to_commit().ok()
 }
 
-fn handle_git(command: &mut Command) -> Result<(), SourceError> {
-    match command.output() {
-        Ok(output) => match output.status.code() {
-            Some(0) => Ok(()),
-            Some(error_code) => Err(SourceError::RemoteSourceError(format!(
-                "Error Code: {}\n{}",
-                error_code,
-                String::from_utf8(output.stderr)
-                    .unwrap_or("Error reading error code from failed git command".to_owned())
-            ))),
-            None => Err(SourceError::RemoteSourceError("Git interrupted by signal".to_owned())),
-        },
-        Err(err) => Err(SourceError::IoError(err)),
+/// Checks out `commit` with a detached HEAD, discarding any changes to the cached working tree.
+fn checkout(repo: &Repository, commit: &Commit) -> Result<(), git2::Error> {
+    repo.checkout_tree(commit.as_object(), Some(CheckoutBuilder::new().force()))?;
+    repo.set_head_detached(commit.id())
+}
+
+fn is_branch(repo: &Repository, gitref: &str) -> bool {
+    repo.find_reference(&format!("refs/remotes/origin/{}", gitref)).is_ok()
+}
+
+fn find_default_branch(repo: &Repository) -> Result<String, SourceError> {
+    for candidate in &["main", "master"] {
+        if is_branch(repo, candidate) {
+            return Ok((*candidate).to_owned());
+        }
     }
+    Err(SourceError::NoDefaultBranch)
 }
 
 #[cfg(test)]
 mod tests {
+    use git2::{Oid, Signature};
+
     use super::*;
 
+    fn commit_file(repo: &Repository, contents: &str, parents: &[&Commit]) -> Oid {
+        let workdir = repo.workdir().unwrap();
+        fs::write(workdir.join("README.md"), contents).unwrap();
+        let mut index = repo.index().unwrap();
+        index.add_path(std::path::Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Archetect", "archetect@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, contents, &tree, parents).unwrap()
+    }
+
+    fn cached_contents(cache: &Utf8Path) -> String {
+        fs::read_to_string(cache.join("README.md")).unwrap()
+    }
+
+    #[test]
+    fn test_credentials_fall_back() {
+        let temp = tempfile::tempdir().unwrap();
+        let ssh_directory = to_utf8_path_buf(temp.path().to_path_buf());
+        fs::write(ssh_directory.join("id_ed25519"), "").unwrap();
+        let mut credentials = Credentials::new(Some(ssh_directory.clone()));
+
+        let url = "ssh://git@example.com/archetype.git";
+        let error = loop {
+            if let Err(error) = credentials.next(url, Some("git"), CredentialType::SSH_KEY) {
+                break error;
+            }
+        };
+        assert_eq!(
+            error.message(),
+            format!(
+                "authentication failed for {}: tried SSH agent, SSH key {}",
+                url,
+                ssh_directory.join("id_ed25519")
+            )
+        );
+
+        let mut credentials = Credentials::new(None);
+        let error = loop {
+            if let Err(error) = credentials.next(url, None, CredentialType::USER_PASS_PLAINTEXT) {
+                break error;
+            }
+        };
+        assert!(error.message().ends_with("tried git credential helpers"));
+    }
+
+    #[test]
+    fn test_cache_git_repo() -> Result<(), SourceError> {
+        let temp = tempfile::tempdir()?;
+        let root = to_utf8_path_buf(temp.path().to_path_buf());
+        let archetect = Archetect::builder().with_temp_layout().unwrap().build().unwrap();
+
+        let origin = Repository::init(root.join("origin"))?;
+        origin.set_head("refs/heads/main")?;
+        let first = commit_file(&origin, "first", &[]);
+        let first = origin.find_commit(first)?;
+        origin.tag_lightweight("v1", first.as_object(), false)?;
+        origin.branch("develop", &first, false)?;
+        let second = commit_file(&origin, "second", &[&first]);
+
+        let url = format!("file://{}", root.join("origin"));
+        let cache = root.join("cache");
+        cache_git_repo(&archetect, &url, &None, &cache, false)?;
+        let repo = Repository::open(&cache)?;
+        assert_eq!(repo.head()?.peel_to_commit()?.id(), second);
+        assert_eq!(cached_contents(&cache), "second");
+
+        cache_git_repo(&archetect, &url, &Some("v1".to_owned()), &cache, false)?;
+        assert_eq!(cached_contents(&cache), "first");
+
+        origin.set_head("refs/heads/develop")?;
+        origin.checkout_head(Some(CheckoutBuilder::new().force()))?;
+        let develop = commit_file(&origin, "develop", &[&first]);
+        fetch(&repo)?;
+        cache_git_repo(&archetect, &url, &Some("develop".to_owned()), &cache, false)?;
+        assert_eq!(repo.head()?.peel_to_commit()?.id(), develop);
+        assert_eq!(cached_contents(&cache), "develop");
+
+        cache_git_repo(&archetect, &url, &Some(first.id().to_string()), &cache, false)?;
+        assert_eq!(cached_contents(&cache), "first");
+
+        let result = cache_git_repo(&archetect, &url, &Some("missing".to_owned()), &cache, false);
+        assert!(matches!(result, Err(SourceError::GitRefNotFound { gitref, .. }) if gitref == "missing"));
+
+        let result = cache_git_repo(&archetect, &format!("{}-missing", url), &None, &root.join("missing"), false);
+        assert!(matches!(result, Err(SourceError::CloneError { .. })));
+
+        Ok(())
+    }
+
     #[test]
     fn test_ssh_git_pattern() {
         let captures = ssh_git_pattern()
@@ -1,3 +1,4 @@
 mod prompts;
+mod rendering;
 mod utils;
 mod test_utils;
\ No newline at end of file
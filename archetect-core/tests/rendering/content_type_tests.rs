@@ -0,0 +1,27 @@
+use rhai::Map;
+
+use crate::rendering::Fixture;
+use crate::test_utils::get_archetype_path;
+
+#[test]
+fn test_content_type_overrides() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let _ = fixture.render(Map::new())?;
+    let destination = fixture.destination();
+
+    // Binary patterns copy files that would otherwise be rendered
+    assert_eq!(
+        std::fs::read_to_string(destination.join("forced.dat"))?,
+        "name: {{ name }}\n"
+    );
+    // Text patterns render files that would otherwise be detected as binary
+    assert_eq!(std::fs::read(destination.join("forced.tpl"))?, b"name:\0example");
+    assert_eq!(std::fs::read_to_string(destination.join("small.txt"))?, "name: example");
+    // Files larger than the maximum renderable size are copied
+    assert_eq!(
+        std::fs::read(destination.join("large.js"))?,
+        std::fs::read(get_archetype_path(file!()).join("contents/large.js"))?
+    );
+
+    Ok(())
+}
@@ -0,0 +1 @@
+var name = "{{ name }}";                                                                                
@@ -0,0 +1,69 @@
+use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::archetype::render_plan::PlannedAction;
+use archetect_core::configuration::Configuration;
+
+use crate::rendering::Fixture;
+
+#[test]
+fn test_dry_run_writes_nothing() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination().join("project");
+    let render_context = RenderContext::new(&destination, Default::default()).with_dry_run(true);
+
+    let _ = fixture.render_with(render_context.clone())?;
+
+    assert!(!destination.exists());
+
+    let entries = render_context.plan().entries();
+    assert_eq!(entries.len(), 2);
+    assert!(entries
+        .iter()
+        .all(|entry| entry.action() == PlannedAction::Create));
+    assert!(entries
+        .iter()
+        .any(|entry| entry.destination() == destination.join("example.txt")));
+    assert!(entries
+        .iter()
+        .any(|entry| entry.destination() == destination.join("src").join("main.rs")));
+
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_reports_existing_files() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+    std::fs::write(destination.join("example.txt"), "Local Edits")?;
+
+    let render_context = fixture.render_with(fixture.context(Default::default()).with_dry_run(true))?;
+
+    assert_eq!(std::fs::read_to_string(destination.join("example.txt"))?, "Local Edits");
+    assert!(!destination.join("src").exists());
+
+    let tree = render_context.plan().to_tree(destination);
+    assert!(tree.contains("example.txt [preserve]"));
+    assert!(tree.contains("main.rs [create]"));
+
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_skips_capture() -> anyhow::Result<()> {
+    let fixture = Fixture::with_configuration(file!(), Configuration::default().with_allow_exec(true))?;
+    let destination = fixture.destination();
+
+    // Commands are not run in a dry run, and capture an empty string
+    let render_context = fixture.context(Default::default()).with_dry_run(true).with_switch("capture");
+    let _ = fixture.render_with(render_context.clone())?;
+    assert!(render_context
+        .plan()
+        .entries()
+        .iter()
+        .any(|entry| entry.destination() == destination.join("skipped.txt")));
+
+    let _ = fixture.render_with(fixture.context(Default::default()).with_switch("capture"))?;
+    assert!(destination.join("captured.txt").is_file());
+    assert!(!destination.join("skipped.txt").exists());
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use assert_matches::assert_matches;
+use camino::{Utf8Path, Utf8PathBuf};
+
+use archetect_api::{api_driver_and_handle, CommandRequest};
+use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::archetype::render_plan::PlannedAction;
+use archetect_core::configuration::Configuration;
+use archetect_core::errors::ArchetypeError;
+
+use crate::rendering::{load_archetype, temp_destination, Fixture};
+use crate::test_utils::get_archetype_path;
+
+/// The files edited by scripts according to the render plan, relative to `destination`.
+fn edited_files(render_context: &RenderContext, destination: &Utf8Path) -> Vec<String> {
+    render_context
+        .plan()
+        .entries()
+        .iter()
+        .filter(|entry| entry.action() == PlannedAction::Edit)
+        .map(|entry| entry.destination().strip_prefix(destination).unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_edits_are_idempotent() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    for iteration in 0..2 {
+        let render_context = fixture.render(Default::default())?;
+
+        // Edits are planned alongside rendered files, but only when they change something
+        let edited = edited_files(&render_context, destination);
+        if iteration == 0 {
+            assert_eq!(edited, vec!["src/lib.rs", "src/lib.rs", ".gitignore", "README.md"]);
+        } else {
+            assert!(edited.is_empty());
+        }
+
+        assert_eq!(
+            std::fs::read_to_string(destination.join("src/lib.rs"))?,
+            "pub mod core;\n// archetect:modules\npub mod service;\n\n// generated\nfn main() {}"
+        );
+        assert_eq!(std::fs::read_to_string(destination.join(".gitignore"))?, "target/\n*.log\n");
+        assert_eq!(
+            std::fs::read_to_string(destination.join("README.md"))?,
+            "# example\n<!-- begin -->\nWelcome to example!\n<!-- end -->"
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_edits_within_transaction() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination().join("project");
+    let _ = fixture.render_with(RenderContext::new(&destination, Default::default()).with_transactional(true))?;
+
+    assert_eq!(std::fs::read_to_string(destination.join(".gitignore"))?, "target/\n*.log\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_edits_skipped_in_dry_run() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+    std::fs::create_dir_all(destination.join("src"))?;
+    std::fs::write(destination.join("src/lib.rs"), "// archetect:modules\nfn main() {}\n")?;
+    std::fs::write(destination.join(".gitignore"), "target/\n")?;
+    std::fs::write(destination.join("README.md"), "<!-- begin -->\n<!-- end -->\n")?;
+
+    let render_context = fixture.render_with(fixture.context(Default::default()).with_dry_run(true))?;
+
+    assert_eq!(std::fs::read_to_string(destination.join(".gitignore"))?, "target/\n");
+    assert_eq!(std::fs::read_to_string(destination.join("README.md"))?, "<!-- begin -->\n<!-- end -->\n");
+    assert_eq!(
+        edited_files(&render_context, destination),
+        vec!["src/lib.rs", "src/lib.rs", ".gitignore", "README.md"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_missing_anchor_fails() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let result = fixture.render_with(fixture.context(Default::default()).with_switch("missing"));
+    assert!(matches!(result, Err(ArchetypeError::ScriptAbortError)));
+    assert_matches!(fixture.handle().receive(), CommandRequest::LogError(message) => {
+        assert!(message.contains("The anchor '// missing' could not be found in 'src/lib.rs'"), "{}", message);
+    });
+
+    Ok(())
+}
+
+#[test]
+fn test_edits_reported_as_rendered_files() -> anyhow::Result<()> {
+    let (driver, handle) = api_driver_and_handle();
+    // Only the render holds on to the archetype, so that requests stop once it completes
+    let driver = driver.with_render_events(true);
+    let archetype = load_archetype(get_archetype_path(file!()).as_str(), driver, Configuration::default())?;
+
+    let (_temp, destination) = temp_destination()?;
+    let render_context = RenderContext::new(&destination, Default::default());
+    let render = std::thread::spawn(move || archetype.render(render_context).map(|_| ()));
+
+    let mut rendered = vec![];
+    for request in handle.requests().iter() {
+        if let CommandRequest::FileRendered(event) = request {
+            let path = Utf8PathBuf::from(event.destination());
+            rendered.push(path.strip_prefix(&destination)?.to_string());
+        }
+    }
+    render.join().unwrap()?;
+
+    // Each edited file is reported once as rendered from its template, and again for every edit made to it
+    rendered.sort();
+    assert_eq!(
+        rendered,
+        vec![".gitignore", ".gitignore", "README.md", "README.md", "src/lib.rs", "src/lib.rs", "src/lib.rs"]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,4 @@
+pub mod core;
+// archetect:modules
+
+fn main() {}
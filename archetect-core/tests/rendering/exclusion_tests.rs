@@ -0,0 +1,39 @@
+use archetect_core::archetype::render_plan::PlannedAction;
+
+use crate::rendering::Fixture;
+
+#[test]
+fn test_blank_names_excluded() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let render_context = fixture.render(Default::default())?;
+
+    let mut entries = std::fs::read_dir(fixture.destination())?
+        .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().to_string()))
+        .collect::<Result<Vec<String>, _>>()?;
+    entries.sort();
+    assert_eq!(entries, vec![".archetect".to_owned(), "README.md".to_owned()]);
+
+    // Excluded files are planned as skipped, while excluded directories are left out entirely
+    let actions = render_context
+        .plan()
+        .entries()
+        .iter()
+        .map(|entry| entry.action())
+        .collect::<Vec<PlannedAction>>();
+    assert_eq!(actions, vec![PlannedAction::Create, PlannedAction::Skip]);
+
+    Ok(())
+}
+
+#[test]
+fn test_names_included_by_switch() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let _ = fixture.render_with(fixture.context(Default::default()).with_switch("docker").with_switch("docs"))?;
+    let destination = fixture.destination();
+
+    assert_eq!(std::fs::read_to_string(destination.join("docker/Dockerfile"))?, "FROM example");
+    assert_eq!(std::fs::read_to_string(destination.join("docker-compose.yaml"))?, "services: {}");
+    assert_eq!(std::fs::read_to_string(destination.join("docs/guide/index.md"))?, "# example");
+
+    Ok(())
+}
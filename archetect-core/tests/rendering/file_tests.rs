@@ -0,0 +1,37 @@
+use assert_matches::assert_matches;
+
+use archetect_api::CommandRequest;
+use archetect_core::errors::ArchetypeError;
+
+use crate::rendering::Fixture;
+
+#[test]
+fn test_render_files() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+    std::fs::write(destination.join("README.md"), "Existing")?;
+
+    let render_context = fixture.render(Default::default())?;
+
+    assert_eq!(
+        std::fs::read_to_string(destination.join("src/entities/customer.rs"))?,
+        "pub struct Customer;"
+    );
+    assert_eq!(std::fs::read_to_string(destination.join("src/entities/order.rs"))?, "pub struct Order;");
+    assert_eq!(std::fs::read_to_string(destination.join("README.md"))?, "# example");
+    assert_eq!(render_context.plan().entries().len(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_render_missing_file() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let result = fixture.render_with(fixture.context(Default::default()).with_switch("missing"));
+    assert!(matches!(result, Err(ArchetypeError::ScriptAbortError)));
+    assert_matches!(fixture.handle().receive(), CommandRequest::LogError(message) => {
+        assert!(message.contains("'missing.rs' could not be found"), "{}", message);
+    });
+
+    Ok(())
+}
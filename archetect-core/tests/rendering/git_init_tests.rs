@@ -0,0 +1,83 @@
+use git2::{ObjectType, Repository, TreeWalkMode, TreeWalkResult};
+use rhai::Map;
+
+use crate::rendering::Fixture;
+
+#[test]
+fn test_git_init_commits_rendered_files() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    let mut answers = Map::new();
+    answers.insert("author_name".into(), "Jane Doe".into());
+    answers.insert("author_email".into(), "jane@example.com".into());
+    let _ = fixture.render_with(fixture.context(answers).with_transactional(true))?;
+
+    let repository = Repository::open(destination)?;
+    let commit = repository.head()?.peel_to_commit()?;
+    assert_eq!(commit.message(), Some("Render example"));
+    assert_eq!(commit.author().name(), Some("Jane Doe"));
+    assert_eq!(commit.author().email(), Some("jane@example.com"));
+    assert_eq!(commit.parent_count(), 0);
+
+    let tree = commit.tree()?;
+    let mut names = tree
+        .iter()
+        .filter_map(|entry| entry.name().map(str::to_owned))
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec![".gitignore", "README.md"]);
+    assert!(destination.join("debug.log").is_file());
+
+    Ok(())
+}
+
+#[test]
+fn test_git_init_commits_render_record() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    let mut answers = Map::new();
+    answers.insert("author_name".into(), "Jane Doe".into());
+    answers.insert("author_email".into(), "jane@example.com".into());
+    let _ = fixture.render_with(fixture.context(answers).with_record(true))?;
+    assert!(destination.join(".archetect/pristine/README.md").is_file());
+
+    let repository = Repository::open(destination)?;
+    let tree = repository.head()?.peel_to_commit()?.tree()?;
+    let mut names = vec![];
+    tree.walk(TreeWalkMode::PreOrder, |directory, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            names.push(format!("{}{}", directory, entry.name().unwrap_or_default()));
+        }
+        TreeWalkResult::Ok
+    })?;
+    assert_eq!(names, vec![".archetect/render.yaml", ".gitignore", "README.md"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_git_init_overridden_by_render_context() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    let _ = fixture.render_with(fixture.context(Map::new()).with_git_init(Some(false)))?;
+
+    assert!(destination.join("README.md").is_file());
+    assert!(!destination.join(".git").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_git_init_skipped_in_dry_run() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    let _ = fixture.render_with(fixture.context(Map::new()).with_dry_run(true))?;
+
+    assert!(!destination.join(".git").exists());
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use crate::rendering::Fixture;
+
+#[test]
+fn test_ignored_paths() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let render_context = fixture.render(Default::default())?;
+    let destination = fixture.destination();
+
+    assert_eq!(std::fs::read_to_string(destination.join("README.md"))?, "example");
+    assert_eq!(std::fs::read_to_string(destination.join("src/main.rs"))?, "fn main() {}");
+    assert_eq!(std::fs::read_to_string(destination.join("src/fixtures/keep.txt"))?, "kept example");
+
+    // Ignored by the manifest
+    assert!(!destination.join("ARCHETYPE.md").exists());
+    // Ignored by .archetectignore files, which are never rendered themselves
+    assert!(!destination.join(".archetectignore").exists());
+    assert!(!destination.join("README.md.swp").exists());
+    assert!(!destination.join("notes").exists());
+    assert!(!destination.join("src/.archetectignore").exists());
+    assert!(!destination.join("src/fixtures/input.txt").exists());
+
+    // Ignored paths are left out before rendering, and so are not part of the plan
+    assert_eq!(render_context.plan().entries().len(), 3);
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use crate::rendering::Fixture;
+
+#[test]
+fn test_line_endings() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let _ = fixture.render(Default::default())?;
+    let destination = fixture.destination();
+
+    // Rendered files use the configured line endings, and end with a newline
+    assert_eq!(
+        std::fs::read_to_string(destination.join("README.md"))?,
+        "# example\r\n\r\nGenerated\r\nproject\r\n"
+    );
+
+    // Unless overridden by a rule
+    assert_eq!(
+        std::fs::read_to_string(destination.join("bin/run.sh"))?,
+        "#!/bin/sh\necho example\n"
+    );
+    assert_eq!(std::fs::read_to_string(destination.join("VERSION"))?, "example-1.0");
+
+    // Copied files are left untouched
+    assert_eq!(std::fs::read_to_string(destination.join("LICENSE"))?, "MIT\nLicense");
+
+    Ok(())
+}
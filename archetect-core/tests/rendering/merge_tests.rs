@@ -0,0 +1,129 @@
+use camino::Utf8Path;
+use rhai::Map;
+
+use archetect_core::archetype::pristine::PristineStore;
+use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::archetype::render_plan::PlannedAction;
+
+use crate::rendering::Fixture;
+
+fn render_version(fixture: &Fixture, destination: &Utf8Path, version: &str) -> anyhow::Result<RenderContext> {
+    let mut answers = Map::new();
+    answers.insert("version".into(), version.into());
+    Ok(fixture.render_with(RenderContext::new(destination, answers).with_record(true))?)
+}
+
+fn edit_setting(settings: &Utf8Path) -> anyhow::Result<()> {
+    let edited = std::fs::read_to_string(settings)?.replace("setting = default", "setting = custom");
+    std::fs::write(settings, edited)?;
+    Ok(())
+}
+
+#[test]
+fn test_merge_local_edits() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+    let settings = destination.join("settings.txt");
+
+    render_version(&fixture, destination, "1.0")?;
+    let pristine = PristineStore::new(destination);
+    assert!(pristine.location(&settings).unwrap().is_file());
+    assert!(destination.join(".archetect/pristine/settings.txt").is_file());
+
+    edit_setting(&settings)?;
+
+    let render_context = render_version(&fixture, destination, "2.0")?;
+
+    let merged = std::fs::read_to_string(&settings)?;
+    assert!(merged.contains("setting = custom"));
+    assert!(merged.contains("version = 2.0"));
+    assert_eq!(render_context.plan().entries()[0].action(), PlannedAction::Merge);
+    assert!(render_context.plan().conflicts().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_conflicting_edits() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+    let settings = destination.join("settings.txt");
+
+    render_version(&fixture, destination, "1.0")?;
+
+    let edited = std::fs::read_to_string(&settings)?.replace("version = 1.0", "version = 1.0-SNAPSHOT");
+    std::fs::write(&settings, edited)?;
+
+    let render_context = render_version(&fixture, destination, "2.0")?;
+
+    let conflicted = std::fs::read_to_string(&settings)?;
+    assert!(conflicted.contains("<<<<<<< ours"));
+    assert!(conflicted.contains("version = 1.0-SNAPSHOT"));
+    assert!(conflicted.contains("version = 2.0"));
+    assert!(conflicted.contains(">>>>>>> theirs"));
+
+    let conflicts = render_context.plan().conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].destination(), settings);
+
+    // The pristine copy tracks the latest render, so that resolved conflicts are not reported again
+    let pristine = PristineStore::new(destination);
+    assert!(std::fs::read_to_string(pristine.location(&settings).unwrap())?.contains("version = 2.0"));
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_unedited_file_updates() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    render_version(&fixture, destination, "1.0")?;
+    let render_context = render_version(&fixture, destination, "2.0")?;
+
+    assert!(std::fs::read_to_string(destination.join("settings.txt"))?.contains("version = 2.0"));
+    assert_eq!(render_context.plan().entries()[0].action(), PlannedAction::Overwrite);
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_after_preserving_render() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    // The first render does not merge, but still records the pristine copy a later merge needs
+    let mut answers = Map::new();
+    answers.insert("version".into(), "1.0".into());
+    let _ = fixture.render_with(fixture.context(answers).with_switch("preserve"))?;
+    edit_setting(&destination.join("settings.txt"))?;
+
+    let render_context = render_version(&fixture, destination, "2.0")?;
+
+    let merged = std::fs::read_to_string(destination.join("settings.txt"))?;
+    assert!(merged.contains("setting = custom"));
+    assert!(merged.contains("version = 2.0"));
+    assert_eq!(render_context.plan().entries()[0].action(), PlannedAction::Merge);
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_after_moving_project() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let original = fixture.destination().join("original");
+
+    render_version(&fixture, &original, "1.0")?;
+    edit_setting(&original.join("settings.txt"))?;
+
+    let moved = fixture.destination().join("moved");
+    std::fs::rename(&original, &moved)?;
+    let render_context = render_version(&fixture, &moved, "2.0")?;
+
+    let merged = std::fs::read_to_string(moved.join("settings.txt"))?;
+    assert!(merged.contains("setting = custom"));
+    assert!(merged.contains("version = 2.0"));
+    assert_eq!(render_context.plan().entries()[0].action(), PlannedAction::Merge);
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use camino::{Utf8Path, Utf8PathBuf};
+use rhai::Map;
+use tempfile::TempDir;
+
+use archetect_api::{api_driver_and_handle, ApiIoDriver, ApiIoHandle};
+use archetect_core::archetype::archetype::Archetype;
+use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::configuration::Configuration;
+use archetect_core::errors::ArchetypeError;
+use archetect_core::Archetect;
+
+use crate::test_utils::get_archetype_path;
+
+mod content_type_tests;
+mod dry_run_tests;
+mod edit_tests;
+mod exclusion_tests;
+mod file_tests;
+mod git_init_tests;
+mod ignore_tests;
+mod line_ending_tests;
+mod merge_tests;
+mod nested_path_tests;
+mod output_sink_tests;
+mod overwrite_tests;
+mod parallel_tests;
+#[cfg(unix)]
+mod post_render_tests;
+mod record_tests;
+mod render_event_tests;
+mod replay_tests;
+mod report_tests;
+mod rules_tests;
+mod structured_merge_tests;
+mod suffix_tests;
+#[cfg(unix)]
+mod symlink_tests;
+mod transaction_tests;
+
+/// The archetype in the fixture directory beside a test file, along with the handle to its driver and an empty
+/// temporary directory to render it into.
+pub struct Fixture {
+    archetype: Archetype,
+    handle: ApiIoHandle,
+    destination: Utf8PathBuf,
+    _temp: TempDir,
+}
+
+impl Fixture {
+    pub fn new(test_file: &str) -> anyhow::Result<Fixture> {
+        Fixture::with_configuration(test_file, Configuration::default())
+    }
+
+    pub fn with_configuration(test_file: &str, configuration: Configuration) -> anyhow::Result<Fixture> {
+        let (driver, handle) = api_driver_and_handle();
+        let archetype = load_archetype(get_archetype_path(test_file).as_str(), driver, configuration)?;
+        let (temp, destination) = temp_destination()?;
+        Ok(Fixture {
+            archetype,
+            handle,
+            destination,
+            _temp: temp,
+        })
+    }
+
+    pub fn archetype(&self) -> &Archetype {
+        &self.archetype
+    }
+
+    pub fn handle(&self) -> &ApiIoHandle {
+        &self.handle
+    }
+
+    pub fn destination(&self) -> &Utf8Path {
+        &self.destination
+    }
+
+    /// A context for rendering into the destination with `answers`.
+    pub fn context(&self, answers: Map) -> RenderContext {
+        RenderContext::new(&self.destination, answers)
+    }
+
+    /// Renders into the destination with `answers`, returning the context used so that its plan can be inspected.
+    pub fn render(&self, answers: Map) -> Result<RenderContext, ArchetypeError> {
+        self.render_with(self.context(answers))
+    }
+
+    pub fn render_with(&self, render_context: RenderContext) -> Result<RenderContext, ArchetypeError> {
+        let _ = self.archetype.render(render_context.clone())?;
+        Ok(render_context)
+    }
+}
+
+/// Loads the archetype at `path`, for tests that need to control where it is rendered from themselves, such as on
+/// another thread.
+pub fn load_archetype(path: &str, driver: ApiIoDriver, configuration: Configuration) -> anyhow::Result<Archetype> {
+    let archetect = Archetect::builder()
+        .with_driver(driver)
+        .with_configuration(configuration)
+        .with_temp_layout()?
+        .build()?;
+    Ok(archetect.new_archetype(path)?)
+}
+
+/// An empty temporary directory, which is removed when the returned guard is dropped.
+pub fn temp_destination() -> anyhow::Result<(TempDir, Utf8PathBuf)> {
+    let temp = tempfile::tempdir()?;
+    let destination = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("UTF-8 temporary directory");
+    Ok((temp, destination))
+}
@@ -0,0 +1,43 @@
+use assert_matches::assert_matches;
+use rhai::Map;
+
+use archetect_api::CommandRequest;
+use archetect_core::archetype::render_context::RenderContext;
+
+use crate::rendering::Fixture;
+
+#[test]
+fn test_names_expand_into_nested_directories() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let _ = fixture.render(Map::new())?;
+    let destination = fixture.destination();
+
+    assert_eq!(
+        std::fs::read_to_string(destination.join("src/main/java/com/acme/billing/Application.java"))?,
+        "package com.acme.billing;"
+    );
+    assert_eq!(
+        std::fs::read_to_string(destination.join("config/application.yaml"))?,
+        "name: com.acme.billing"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_names_escaping_destination_rejected() -> anyhow::Result<()> {
+    for config_path in ["../outside.yaml", "config/../../outside.yaml", "/tmp/outside.yaml"] {
+        let fixture = Fixture::new(file!())?;
+        let destination = fixture.destination().join("project");
+        let mut answers = Map::new();
+        answers.insert("config_path".into(), config_path.into());
+        assert!(fixture.render_with(RenderContext::new(&destination, answers)).is_err());
+
+        assert_matches!(fixture.handle().receive(), CommandRequest::LogError(message) => {
+            assert!(message.contains(config_path), "{}", message);
+        });
+        assert!(!fixture.destination().join("outside.yaml").exists());
+    }
+
+    Ok(())
+}
@@ -0,0 +1 @@
+package {{ package_name }};
\ No newline at end of file
@@ -0,0 +1,114 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+
+use camino::Utf8Path;
+use flate2::read::GzDecoder;
+use rhai::Map;
+
+use archetect_core::archetype::output_sink::{ArchiveFormat, ArchiveSink, MemorySink, OutputSink};
+use archetect_core::archetype::render_context::RenderContext;
+
+use crate::rendering::{temp_destination, Fixture};
+
+#[test]
+fn test_render_to_memory() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    let sink = MemorySink::new();
+    let _ = fixture.render_with(fixture.context(Map::new()).with_record(true).with_sink(sink.clone()))?;
+
+    assert_eq!(
+        sink.contents(destination.join("README.md")),
+        Some(b"# example".to_vec())
+    );
+    assert_eq!(
+        sink.contents(destination.join("src/lib.rs")),
+        Some(b"// example\npub const NAME: &str = \"example\";\n".to_vec())
+    );
+    assert!(sink.is_file(&destination.join(".archetect/render.yaml")));
+    assert_eq!(std::fs::read_dir(destination)?.count(), 0);
+
+    Ok(())
+}
+
+/// The entries of an extracted archive by path, with their contents and modes.
+type Extracted = BTreeMap<String, (Vec<u8>, u32)>;
+
+fn extract_tar<R: Read>(reader: R) -> anyhow::Result<Extracted> {
+    let mut archive = tar::Archive::new(reader);
+    let mut extracted = BTreeMap::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().trim_end_matches('/').to_owned();
+        let mode = entry.header().mode()?;
+        let mut contents = vec![];
+        entry.read_to_end(&mut contents)?;
+        extracted.insert(path, (contents, mode));
+    }
+    Ok(extracted)
+}
+
+fn extract_zip(path: &Utf8Path) -> anyhow::Result<Extracted> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+    let mut extracted = BTreeMap::new();
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index)?;
+        let path = file.name().trim_end_matches('/').to_owned();
+        let mode = file.unix_mode().unwrap_or_default() & 0o7777;
+        let mut contents = vec![];
+        file.read_to_end(&mut contents)?;
+        extracted.insert(path, (contents, mode));
+    }
+    Ok(extracted)
+}
+
+#[test]
+fn test_render_to_archive() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+
+    // Pristine copies of rendered files travel with the project
+    let expected: Extracted = BTreeMap::from([
+        (".archetect".to_owned(), (vec![], 0o755)),
+        (".archetect/pristine".to_owned(), (vec![], 0o755)),
+        (".archetect/pristine/README.md".to_owned(), (b"# example".to_vec(), 0o644)),
+        (".archetect/pristine/bin".to_owned(), (vec![], 0o755)),
+        (".archetect/pristine/bin/run.sh".to_owned(), (b"#!/bin/sh\necho example".to_vec(), 0o644)),
+        (".archetect/pristine/src".to_owned(), (vec![], 0o755)),
+        (".archetect/pristine/src/lib.rs".to_owned(), (b"// example".to_vec(), 0o644)),
+        ("README.md".to_owned(), (b"# example".to_vec(), 0o644)),
+        ("bin".to_owned(), (vec![], 0o755)),
+        ("bin/run.sh".to_owned(), (b"#!/bin/sh\necho example".to_vec(), 0o755)),
+        ("src".to_owned(), (vec![], 0o755)),
+        (
+            "src/lib.rs".to_owned(),
+            (b"// example\npub const NAME: &str = \"example\";\n".to_vec(), 0o644),
+        ),
+    ]);
+
+    for (name, format) in [
+        ("project.tar", ArchiveFormat::Tar),
+        ("project.tar.gz", ArchiveFormat::TarGz),
+        ("project.zip", ArchiveFormat::Zip),
+    ] {
+        let (_temp, root) = temp_destination()?;
+        let destination = root.join("project");
+        let archive = root.join(name);
+
+        let sink = ArchiveSink::new(&archive, &destination, format);
+        let _ = fixture.render_with(RenderContext::new(&destination, Map::new()).with_sink(sink.clone()))?;
+        assert!(!archive.exists());
+
+        sink.finish()?;
+        assert!(!destination.exists());
+        let extracted = match format {
+            ArchiveFormat::Tar => extract_tar(File::open(&archive)?)?,
+            ArchiveFormat::TarGz => extract_tar(GzDecoder::new(File::open(&archive)?))?,
+            ArchiveFormat::Zip => extract_zip(&archive)?,
+        };
+        assert_eq!(extracted, expected, "{} did not round trip", name);
+    }
+
+    Ok(())
+}
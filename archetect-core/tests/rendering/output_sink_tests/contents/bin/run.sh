@@ -0,0 +1,2 @@
+#!/bin/sh
+echo {{ name }}
\ No newline at end of file
@@ -0,0 +1,69 @@
+use std::thread::JoinHandle;
+
+use assert_matches::assert_matches;
+use camino::Utf8PathBuf;
+
+use archetect_api::{CommandRequest, CommandResponse, OverwriteDecision};
+
+use crate::rendering::Fixture;
+
+/// Edits every file the fixture renders, then starts rendering over them on another thread, so that the overwrite
+/// prompts can be answered through the fixture's handle.
+fn render_over_edits(fixture: &Fixture) -> anyhow::Result<JoinHandle<bool>> {
+    for name in ["first", "second", "third"] {
+        std::fs::write(fixture.destination().join(format!("{}.txt", name)), format!("{} edited", name))?;
+    }
+    let archetype = fixture.archetype().clone();
+    let render_context = fixture.context(Default::default());
+    Ok(std::thread::spawn(move || archetype.render(render_context).is_ok()))
+}
+
+#[test]
+fn test_overwrite_prompt_yes_and_no() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let (handle, destination) = (fixture.handle(), fixture.destination());
+    let render = render_over_edits(&fixture)?;
+
+    let mut overwritten = vec![];
+    for _ in 0..3 {
+        assert_matches!(handle.receive(), CommandRequest::PromptForOverwrite(prompt_info) => {
+            let name = Utf8PathBuf::from(prompt_info.destination()).file_stem().unwrap().to_owned();
+            assert_eq!(prompt_info.existing(), format!("{} edited", name));
+            assert_eq!(prompt_info.proposed(), format!("{} example", name));
+            let decision = if name == "second" { OverwriteDecision::Yes } else { OverwriteDecision::No };
+            if decision.overwrite() {
+                overwritten.push(name);
+            }
+            handle.respond(CommandResponse::Overwrite(decision));
+        });
+    }
+    assert!(render.join().unwrap());
+
+    assert_eq!(overwritten, vec!["second".to_owned()]);
+    assert_eq!(std::fs::read_to_string(destination.join("first.txt"))?, "first edited");
+    assert_eq!(std::fs::read_to_string(destination.join("second.txt"))?, "second example");
+    assert_eq!(std::fs::read_to_string(destination.join("third.txt"))?, "third edited");
+
+    Ok(())
+}
+
+#[test]
+fn test_overwrite_prompt_all() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let (handle, destination) = (fixture.handle(), fixture.destination());
+    let render = render_over_edits(&fixture)?;
+
+    // Only the first file is prompted for, with the decision applied to all remaining files
+    assert_matches!(handle.receive(), CommandRequest::PromptForOverwrite(_));
+    handle.respond(CommandResponse::Overwrite(OverwriteDecision::All));
+    assert!(render.join().unwrap());
+
+    for name in ["first", "second", "third"] {
+        assert_eq!(
+            std::fs::read_to_string(destination.join(format!("{}.txt", name)))?,
+            format!("{} example", name)
+        );
+    }
+
+    Ok(())
+}
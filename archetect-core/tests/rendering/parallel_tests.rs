@@ -0,0 +1,85 @@
+use assert_matches::assert_matches;
+use camino::{Utf8Path, Utf8PathBuf};
+
+use archetect_api::{api_driver_and_handle, CommandRequest};
+use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::configuration::Configuration;
+
+use crate::rendering::{load_archetype, temp_destination};
+
+const FILE_COUNT: usize = 200;
+
+/// Writes an archetype rendering a large number of files spread across several directories.
+fn write_archetype(root: &Utf8Path, broken: &[usize]) -> anyhow::Result<()> {
+    std::fs::write(
+        root.join("archetype.yaml"),
+        "---\ndescription: \"Parallel Tests\"\n\nrequires:\n  archetect: \"2.0.0\"\n",
+    )?;
+    std::fs::write(
+        root.join("archetype.rhai"),
+        "let context = #{ name: \"example\" };\n\nDirectory(\"contents\").render(context);\n",
+    )?;
+    for index in 0..FILE_COUNT {
+        let directory = root.join("contents").join(format!("{{{{ name }}}}-{}", index % 10));
+        std::fs::create_dir_all(&directory)?;
+        let contents = if broken.contains(&index) {
+            format!("{} {{{{ missing }}}}", index)
+        } else {
+            format!("{} {{{{ name }}}}", index)
+        };
+        std::fs::write(directory.join(format!("file-{:03}.txt", index)), contents)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parallel_render() -> anyhow::Result<()> {
+    let (_archetype_dir, root) = temp_destination()?;
+    write_archetype(&root, &[])?;
+
+    let (driver, _handle) = api_driver_and_handle();
+    let archetype = load_archetype(root.as_str(), driver, Configuration::default())?;
+
+    let (_temp, destination) = temp_destination()?;
+    let render_context = RenderContext::new(&destination, Default::default());
+    let _ = archetype.render(render_context.clone())?;
+
+    for index in 0..FILE_COUNT {
+        let path = destination.join(format!("example-{}/file-{:03}.txt", index % 10, index));
+        assert_eq!(std::fs::read_to_string(path)?, format!("{} example", index));
+    }
+
+    // The plan is recorded in path order, regardless of the order in which files finished rendering
+    let destinations = render_context
+        .plan()
+        .entries()
+        .iter()
+        .map(|entry| entry.destination().to_path_buf())
+        .collect::<Vec<Utf8PathBuf>>();
+    let mut sorted = destinations.clone();
+    sorted.sort();
+    assert_eq!(destinations.len(), FILE_COUNT);
+    assert_eq!(destinations, sorted);
+
+    Ok(())
+}
+
+#[test]
+fn test_parallel_render_reports_first_error() -> anyhow::Result<()> {
+    let (_archetype_dir, root) = temp_destination()?;
+    write_archetype(&root, &[21, 197])?;
+
+    let (driver, handle) = api_driver_and_handle();
+    let archetype = load_archetype(root.as_str(), driver, Configuration::default())?;
+
+    let (_temp, destination) = temp_destination()?;
+    assert!(archetype.render(RenderContext::new(&destination, Default::default())).is_err());
+
+    // The first failing file in path order is reported, not whichever worker failed first
+    assert_matches!(handle.receive(), CommandRequest::LogError(message) => {
+        assert!(message.contains("file-021.txt"), "{}", message);
+        assert!(!message.contains("file-197.txt"), "{}", message);
+    });
+
+    Ok(())
+}
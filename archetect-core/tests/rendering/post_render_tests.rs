@@ -0,0 +1,166 @@
+use std::thread::JoinHandle;
+
+use assert_matches::assert_matches;
+
+use archetect_api::{api_driver_and_handle, ApiIoHandle, CommandRequest, CommandResponse};
+use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::configuration::Configuration;
+use archetect_core::errors::ArchetypeError;
+
+use crate::rendering::{load_archetype, temp_destination};
+use crate::test_utils::get_archetype_path;
+
+fn render(
+    archetype: &str,
+    configuration: Configuration,
+    render_context: RenderContext,
+) -> anyhow::Result<(ApiIoHandle, JoinHandle<Result<(), ArchetypeError>>)> {
+    let (driver, handle) = api_driver_and_handle();
+    let archetype = load_archetype(archetype, driver, configuration)?;
+    let render = std::thread::spawn(move || archetype.render(render_context).map(|_| ()));
+    Ok((handle, render))
+}
+
+fn warnings(handle: &ApiIoHandle) -> Vec<String> {
+    handle
+        .requests()
+        .iter()
+        .filter_map(|request| match request {
+            CommandRequest::LogWarn(message) => Some(message),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_post_render_hooks_allowed() -> anyhow::Result<()> {
+    let (_temp, destination) = temp_destination()?;
+    let render_context = RenderContext::new(&destination, Default::default()).with_switch("web");
+    let (handle, render) = render(
+        get_archetype_path(file!()).as_str(),
+        Configuration::default().with_allow_exec(true),
+        render_context,
+    )?;
+
+    let warnings = warnings(&handle);
+    render.join().unwrap()?;
+
+    assert!(destination.join("formatted").exists());
+    assert!(destination.join("web/formatted").exists());
+    // Failing hooks are reported, without failing the render
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("exited with code 3:\nbroken"));
+
+    Ok(())
+}
+
+#[test]
+fn test_post_render_hooks_switched_off() -> anyhow::Result<()> {
+    let (_temp, destination) = temp_destination()?;
+    let render_context = RenderContext::new(&destination, Default::default());
+    let (handle, render) = render(
+        get_archetype_path(file!()).as_str(),
+        Configuration::default().with_allow_exec(true),
+        render_context,
+    )?;
+
+    let _ = warnings(&handle);
+    render.join().unwrap()?;
+
+    assert!(destination.join("formatted").exists());
+    assert!(!destination.join("web/formatted").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_post_render_hooks_transactional() -> anyhow::Result<()> {
+    let (_temp, destination) = temp_destination()?;
+    let render_context = RenderContext::new(&destination, Default::default()).with_transactional(true);
+    let (handle, render) = render(
+        get_archetype_path(file!()).as_str(),
+        Configuration::default().with_allow_exec(true),
+        render_context,
+    )?;
+
+    let _ = warnings(&handle);
+    render.join().unwrap()?;
+
+    // Hooks run against the committed destination, rather than the staging directory
+    assert!(destination.join("formatted").exists());
+    assert!(destination.join("README.md").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_post_render_hooks_disallowed() -> anyhow::Result<()> {
+    let (_temp, destination) = temp_destination()?;
+    let render_context = RenderContext::new(&destination, Default::default());
+    let (handle, render) = render(
+        get_archetype_path(file!()).as_str(),
+        Configuration::default().with_allow_exec(false),
+        render_context,
+    )?;
+
+    let warnings = warnings(&handle);
+    render.join().unwrap()?;
+
+    assert!(destination.join("README.md").exists());
+    assert!(!destination.join("formatted").exists());
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings[0].contains("executing commands is not allowed"));
+
+    Ok(())
+}
+
+#[test]
+fn test_post_render_hooks_prompt() -> anyhow::Result<()> {
+    let (_temp, destination) = temp_destination()?;
+    let render_context = RenderContext::new(&destination, Default::default());
+    let (handle, render) = render(
+        get_archetype_path(file!()).as_str(),
+        Configuration::default(),
+        render_context,
+    )?;
+
+    let mut prompted = false;
+    for request in handle.requests().iter() {
+        if let CommandRequest::PromptForBool(prompt_info) = request {
+            assert!(prompt_info.message().contains("touch formatted"));
+            prompted = true;
+            handle.respond(CommandResponse::Boolean(false));
+        }
+    }
+    render.join().unwrap()?;
+
+    assert!(prompted);
+    assert!(!destination.join("formatted").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_post_render_hooks_required() -> anyhow::Result<()> {
+    let (_temp, root) = temp_destination()?;
+    let source = root.join("archetype");
+    std::fs::create_dir_all(source.join("contents"))?;
+    std::fs::write(
+        source.join("archetype.yaml"),
+        "description: Required Hook\nrequires:\n  archetect: \"2.0.0\"\npost_render:\n  - command: \"false\"\n    required: true\n",
+    )?;
+    std::fs::write(source.join("archetype.rhai"), "Directory(\"contents\").render(#{});\n")?;
+    let render_context = RenderContext::new(root.join("destination"), Default::default());
+    let (handle, render) = render(
+        source.as_str(),
+        Configuration::default().with_allow_exec(true),
+        render_context,
+    )?;
+
+    let _ = warnings(&handle);
+    assert_matches!(render.join().unwrap(), Err(ArchetypeError::PostRenderHookFailed { command, .. }) => {
+        assert_eq!(command, "false");
+    });
+
+    Ok(())
+}
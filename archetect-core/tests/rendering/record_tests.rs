@@ -0,0 +1,84 @@
+use rhai::Map;
+
+use archetect_api::api_driver_and_handle;
+use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::archetype::render_record::RenderRecord;
+use archetect_core::configuration::Configuration;
+
+use crate::rendering::{load_archetype, Fixture};
+
+#[test]
+fn test_render_record_written() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    let mut answers = Map::new();
+    answers.insert("project_name".into(), "Example Project".into());
+    answers.insert("author".into(), "Jane Doe".into());
+    answers.insert("unused".into(), "Unused".into());
+    let _ = fixture.render_with(fixture.context(answers).with_switch("testing").with_record(true))?;
+
+    assert_eq!(std::fs::read_to_string(destination.join("README.md"))?, "example-project");
+
+    let record = RenderRecord::load(destination)?;
+    assert!(record.source().ends_with("record_tests"));
+    assert_eq!(record.archetect_version(), env!("CARGO_PKG_VERSION"));
+    assert_eq!(record.switches(), &["testing".to_string()]);
+    assert_eq!(record.answers().len(), 3);
+    assert_eq!(record.answers().get("project_name").unwrap().to_string(), "Example Project");
+    assert_eq!(record.answers().get("author").unwrap().to_string(), "Jane Doe");
+    assert_eq!(record.answers().get("license").unwrap().to_string(), "MIT");
+
+    assert_eq!(record.components().len(), 1);
+    let component = &record.components()[0];
+    assert_eq!(component.destination(), "service");
+    assert!(component.source().unwrap().ends_with("record_child"));
+    assert_eq!(component.answers().get("port").unwrap().as_int().unwrap(), 8080);
+
+    // Pristine copies of rendered files are kept beside the record, including those of components
+    assert_eq!(std::fs::read_to_string(destination.join(".archetect/pristine/README.md"))?, "example-project");
+    assert!(destination.join(".archetect/pristine/service").is_dir());
+
+    Ok(())
+}
+
+#[test]
+fn test_render_record_not_written_by_default() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    let mut answers = Map::new();
+    answers.insert("project_name".into(), "Example Project".into());
+    answers.insert("author".into(), "Jane Doe".into());
+    let _ = fixture.render(answers)?;
+
+    assert!(!RenderRecord::location(destination).exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_render_record_replay() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    let mut answers = Map::new();
+    answers.insert("project_name".into(), "Example Project".into());
+    answers.insert("author".into(), "Jane Doe".into());
+    let _ = fixture.render_with(fixture.context(answers).with_record(true))?;
+
+    std::fs::remove_file(destination.join("README.md"))?;
+
+    // Replay headless, supplying nothing beyond what was recorded
+    let (driver, _handle) = api_driver_and_handle();
+    let record = RenderRecord::load(destination)?;
+    let configuration = Configuration::default().with_headless(true);
+    let archetype = load_archetype(&record.replay_source(None), driver, configuration)?;
+    let render_context = record.apply_to(RenderContext::new(destination, Map::new()).with_record(true));
+    let _ = archetype.render(render_context)?;
+
+    assert_eq!(std::fs::read_to_string(destination.join("README.md"))?, "example-project");
+    assert_eq!(RenderRecord::load(destination)?.answers().len(), record.answers().len());
+
+    Ok(())
+}
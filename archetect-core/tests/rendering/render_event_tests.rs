@@ -0,0 +1,121 @@
+use std::thread::JoinHandle;
+
+use camino::Utf8PathBuf;
+use rhai::Map;
+
+use archetect_api::{api_driver_and_handle, ApiIoHandle, CommandRequest, CommandResponse, OverwriteDecision};
+use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::configuration::Configuration;
+use archetect_core::errors::ArchetypeError;
+
+use crate::rendering::{load_archetype, temp_destination};
+use crate::test_utils::get_archetype_path;
+
+fn start_render(
+    destination: &Utf8PathBuf,
+    answers: Map,
+    render_events: bool,
+) -> anyhow::Result<(ApiIoHandle, JoinHandle<Result<(), ArchetypeError>>)> {
+    let (driver, handle) = api_driver_and_handle();
+    let driver = driver.with_render_events(render_events);
+    let archetype = load_archetype(get_archetype_path(file!()).as_str(), driver, Configuration::default())?;
+    let render_context = RenderContext::new(destination, answers);
+    let render = std::thread::spawn(move || archetype.render(render_context).map(|_| ()));
+    Ok((handle, render))
+}
+
+/// Receives every request until the render completes, answering overwrite prompts with `decision`, and describes
+/// each event relative to `destination`.
+fn collect_events(handle: &ApiIoHandle, destination: &Utf8PathBuf, decision: OverwriteDecision) -> Vec<String> {
+    let relative = |path: &str| match Utf8PathBuf::from(path).strip_prefix(destination) {
+        Ok(path) if path.as_str().is_empty() => ".".to_owned(),
+        Ok(path) => path.to_string(),
+        Err(_) => path.to_owned(),
+    };
+    let mut events = vec![];
+    for request in handle.requests().iter() {
+        match request {
+            CommandRequest::PromptForOverwrite(_) => handle.respond(CommandResponse::Overwrite(decision)),
+            CommandRequest::DirectoryCreated(path) => events.push(format!("directory {}", relative(&path))),
+            CommandRequest::FileRendered(event) => events.push(format!("rendered {}", relative(event.destination()))),
+            CommandRequest::FileCopied(event) => events.push(format!("copied {}", relative(event.destination()))),
+            CommandRequest::FilePreserved(event) => events.push(format!("preserved {}", relative(event.destination()))),
+            CommandRequest::OverwriteSkipped(event) => {
+                events.push(format!("skipped {}", relative(event.destination())))
+            }
+            _ => (),
+        }
+    }
+    events
+}
+
+#[test]
+fn test_render_events() -> anyhow::Result<()> {
+    let (_temp, root) = temp_destination()?;
+    let destination = root.join("project");
+
+    let (handle, render) = start_render(&destination, Map::new(), true)?;
+    let events = collect_events(&handle, &destination, OverwriteDecision::No);
+    render.join().unwrap()?;
+
+    assert_eq!(
+        events,
+        vec![
+            "directory .",
+            "directory src",
+            "copied LICENSE",
+            "rendered README.md",
+            "rendered src/example.txt",
+        ]
+    );
+
+    // Existing files are preserved, whether rendered or copied
+    let (handle, render) = start_render(&destination, Map::new(), true)?;
+    let events = collect_events(&handle, &destination, OverwriteDecision::No);
+    render.join().unwrap()?;
+
+    assert_eq!(
+        events,
+        vec!["preserved LICENSE", "preserved README.md", "preserved src/example.txt"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_render_events_when_prompted() -> anyhow::Result<()> {
+    let (_temp, destination) = temp_destination()?;
+    std::fs::write(destination.join("README.md"), "# edited")?;
+
+    let mut answers = Map::new();
+    answers.insert("prompt".into(), true.into());
+    let (handle, render) = start_render(&destination, answers, true)?;
+    let events = collect_events(&handle, &destination, OverwriteDecision::No);
+    render.join().unwrap()?;
+
+    assert_eq!(
+        events,
+        vec![
+            "directory src",
+            "copied LICENSE",
+            "skipped README.md",
+            "rendered src/example.txt"
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_render_events_disabled() -> anyhow::Result<()> {
+    let (_temp, destination) = temp_destination()?;
+
+    let (handle, render) = start_render(&destination, Map::new(), false)?;
+    let events = collect_events(&handle, &destination, OverwriteDecision::No);
+    render.join().unwrap()?;
+
+    assert!(events.is_empty());
+    assert!(destination.join("src/example.txt").exists());
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use assert_matches::assert_matches;
+use rhai::Map;
+
+use archetect_api::{CommandRequest, CommandResponse, PromptInfo};
+use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::archetype::render_record::RenderRecord;
+use archetect_core::configuration::Configuration;
+
+use crate::rendering::{temp_destination, Fixture};
+use crate::test_utils::TestHarness;
+
+#[test]
+fn test_replay_answers_components() -> anyhow::Result<()> {
+    let (_temp, destination) = temp_destination()?;
+
+    let mut answers = Map::new();
+    answers.insert("project_name".into(), "example".into());
+    let render_context = RenderContext::new(&destination, answers).with_record(true);
+    let harness = TestHarness::new(file!(), Configuration::default(), render_context)?;
+    assert_matches!(harness.receive(), CommandRequest::PromptForText(_));
+    harness.respond(CommandResponse::String("An example".into()));
+    assert_matches!(harness.receive(), CommandRequest::PromptForInt(prompt_info) => {
+        assert_eq!(prompt_info.message(), "Port:");
+    });
+    harness.respond(CommandResponse::Integer(8080));
+    assert!(harness.render_succeeded());
+
+    std::fs::remove_dir_all(destination.join("service"))?;
+
+    // Replay headless, where the component's prompt can only be answered from the record
+    let replay = Fixture::with_configuration(file!(), Configuration::default().with_headless(true))?;
+    let record = RenderRecord::load(&destination)?;
+    let _ = replay.render_with(record.apply_to(RenderContext::new(&destination, Map::new()).with_record(true)))?;
+
+    assert_eq!(std::fs::read_to_string(destination.join("service/config.yaml"))?, "port: 8080");
+    let replayed = RenderRecord::load(&destination)?;
+    assert_eq!(replayed.components().len(), 1);
+    assert_eq!(replayed.components()[0].answers().get("port").unwrap().as_int().unwrap(), 8080);
+    assert_eq!(replayed.answers().get("description").unwrap().to_string(), "An example");
+
+    Ok(())
+}
+
+#[test]
+fn test_replay_skipped_optional_prompt() -> anyhow::Result<()> {
+    let (_temp, destination) = temp_destination()?;
+
+    let mut answers = Map::new();
+    answers.insert("project_name".into(), "example".into());
+    let render_context = RenderContext::new(&destination, answers).with_record(true);
+    let harness = TestHarness::new(file!(), Configuration::default(), render_context)?;
+    assert_matches!(harness.receive(), CommandRequest::PromptForText(prompt_info) => {
+        assert_eq!(prompt_info.message(), "Description:");
+    });
+    harness.respond(CommandResponse::None);
+    assert_matches!(harness.receive(), CommandRequest::PromptForInt(_));
+    harness.respond(CommandResponse::Integer(8080));
+    assert!(harness.render_succeeded());
+
+    let record = RenderRecord::load(&destination)?;
+    assert!(record.answers().get("description").is_none());
+
+    let replay = Fixture::with_configuration(file!(), Configuration::default().with_headless(true))?;
+    let _ = replay.render_with(record.apply_to(RenderContext::new(&destination, Map::new()).with_record(true)))?;
+
+    assert_eq!(std::fs::read_to_string(destination.join("README.md"))?, "example");
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use rhai::Map;
+use sha2::{Digest, Sha256};
+
+use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::archetype::render_plan::PlannedAction;
+use archetect_core::archetype::render_report::RenderReport;
+
+use crate::rendering::Fixture;
+
+#[test]
+fn test_render_report() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination().join("project");
+
+    let mut answers = Map::new();
+    answers.insert("project_name".into(), "Example Project".into());
+    answers.insert("author".into(), "Jane Doe".into());
+    let render_context = fixture.render_with(RenderContext::new(&destination, answers))?;
+
+    let report = RenderReport::capture(&render_context)?;
+    let files = report
+        .files()
+        .iter()
+        .map(|file| (file.destination().as_str(), file.action(), file.size()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        files,
+        vec![
+            ("README.md", PlannedAction::Create, Some(15)),
+            ("service/config.yaml", PlannedAction::Create, Some(10)),
+        ]
+    );
+    assert!(report.files()[0].source().ends_with("report_tests/contents/README.md"));
+    assert_eq!(
+        report.files()[0].sha256(),
+        Some(format!("{:x}", Sha256::digest(b"example-project")).as_str())
+    );
+
+    for path in ["report.json", "report.yaml"] {
+        let path = fixture.destination().join(path);
+        report.write(&path)?;
+        let written: serde_json::Value = match path.extension() {
+            Some("json") => serde_json::from_str(&std::fs::read_to_string(&path)?)?,
+            _ => serde_yaml::from_str(&std::fs::read_to_string(&path)?)?,
+        };
+        assert_eq!(written["files"][1]["destination"], "service/config.yaml");
+        assert_eq!(written["files"][1]["action"], "create");
+        assert_eq!(written["files"][1]["size"], 10);
+        assert_eq!(written["answers"]["author"], "Jane Doe");
+        assert_eq!(written["components"][0]["destination"], "service");
+        assert_eq!(written["components"][0]["answers"]["port"], 8080);
+    }
+
+    Ok(())
+}
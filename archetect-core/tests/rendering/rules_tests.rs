@@ -0,0 +1,59 @@
+use archetect_core::archetype::render_plan::PlannedAction;
+
+use crate::rendering::Fixture;
+
+#[test]
+fn test_templating_rules() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let render_context = fixture.render(Default::default())?;
+    let destination = fixture.destination();
+
+    assert_eq!(std::fs::read_to_string(destination.join("README.md"))?, "example");
+    assert_eq!(
+        std::fs::read_to_string(destination.join(".github/workflows/build.yml"))?,
+        "steps:\n  - run: echo ${{ github.sha }}\n"
+    );
+    assert!(!destination.join("README.md.orig").exists());
+    assert!(!destination.join("scratch").exists());
+    assert!(!destination.join("docker").exists());
+
+    let skipped = render_context
+        .plan()
+        .entries()
+        .iter()
+        .filter(|entry| entry.action() == PlannedAction::Skip)
+        .count();
+    assert_eq!(skipped, 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_templating_rules_with_switch() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let _ = fixture.render_with(fixture.context(Default::default()).with_switch("docker"))?;
+    let destination = fixture.destination();
+
+    assert_eq!(std::fs::read_to_string(destination.join("docker/Dockerfile"))?, "FROM example");
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_templating_rules_modes() -> anyhow::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let fixture = Fixture::new(file!())?;
+    let _ = fixture.render(Default::default())?;
+    let destination = fixture.destination();
+
+    let mode = |path: &str| -> anyhow::Result<u32> {
+        Ok(std::fs::metadata(destination.join(path))?.permissions().mode() & 0o7777)
+    };
+    assert_eq!(mode("bin/run.sh")?, 0o755);
+    assert_eq!(mode("gradlew")?, 0o750);
+    assert_eq!(std::fs::read_to_string(destination.join("bin/run.sh"))?, "#!/bin/sh\necho example");
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::rendering::Fixture;
+
+#[test]
+fn test_structured_merge() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    // Rendering again finds every merge already applied, and leaves the files as they were
+    for _ in 0..2 {
+        let _ = fixture.render(Default::default())?;
+
+        assert_eq!(
+            std::fs::read_to_string(destination.join("Cargo.toml"))?,
+            "[package]\nname = \"example\"\nversion = \"0.1.0\" # bumped on release\n\n\
+             [dependencies]\n# Serialization\nserde = \"1\"\nanyhow = \"1\"\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(destination.join("package.json"))?,
+            "{\n  \"name\": \"example\",\n  \"files\": [\n    \"dist\",\n    \"lib\"\n  ],\n  \"scripts\": {\n    \
+             \"test\": \"jest\"\n  }\n}\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(destination.join("compose.yaml"))?,
+            "services:\n  web:\n    image: nginx\n  db:\n    image: postgres\n"
+        );
+        assert_eq!(std::fs::read_to_string(destination.join("settings.conf"))?, "{\n  \"verbose\": true\n}\n");
+    }
+
+    Ok(())
+}
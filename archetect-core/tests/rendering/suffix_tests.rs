@@ -0,0 +1,53 @@
+use archetect_core::archetype::render_plan::PlannedAction;
+
+use crate::rendering::Fixture;
+
+#[test]
+fn test_template_suffixes() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let _ = fixture.render(Default::default())?;
+    let destination = fixture.destination();
+
+    // Suffixed files are rendered and written without their suffix
+    assert_eq!(std::fs::read_to_string(destination.join("README.md"))?, "# example");
+    assert_eq!(std::fs::read_to_string(destination.join("example.yaml"))?, "name: example");
+    assert!(!destination.join("README.md.jinja").exists());
+
+    // Everything else is copied verbatim
+    assert_eq!(
+        std::fs::read_to_string(destination.join(".github/workflows/build.yml"))?,
+        "run: echo ${{ github.sha }}"
+    );
+    assert_eq!(
+        std::fs::read_to_string(destination.join("charts/templates/deployment.yaml"))?,
+        "name: {{ .Release.Name }}"
+    );
+
+    // Unless a rule says otherwise
+    assert_eq!(std::fs::read_to_string(destination.join("VERSION"))?, "example-1.0");
+
+    Ok(())
+}
+
+#[test]
+fn test_copied_files_preserved() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let _ = fixture.render(Default::default())?;
+    let destination = fixture.destination();
+
+    let workflow = destination.join(".github/workflows/build.yml");
+    std::fs::write(&workflow, "run: make")?;
+
+    let render_context = fixture.render(Default::default())?;
+
+    assert_eq!(std::fs::read_to_string(&workflow)?, "run: make");
+    let entry = render_context
+        .plan()
+        .entries()
+        .iter()
+        .find(|entry| entry.destination() == workflow)
+        .map(|entry| entry.action());
+    assert_eq!(entry, Some(PlannedAction::Preserve));
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use std::os::unix::fs::symlink;
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+use archetect_api::api_driver_and_handle;
+use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::configuration::Configuration;
+
+use crate::rendering::{load_archetype, temp_destination};
+
+/// Writes an archetype whose contents include links to a file, to a directory, and to a templated target.
+fn write_archetype(root: &Utf8Path, symlinks: &str) -> anyhow::Result<()> {
+    std::fs::write(
+        root.join("archetype.yaml"),
+        format!(
+            "---\ndescription: \"Symlink Tests\"\n\nrequires:\n  archetect: \"2.0.0\"\n\ntemplating:\n  symlinks: {}\n",
+            symlinks
+        ),
+    )?;
+    std::fs::write(
+        root.join("archetype.rhai"),
+        "let context = #{ name: \"example\" };\n\nDirectory(\"contents\").render(context);\n",
+    )?;
+    let contents = root.join("contents");
+    std::fs::create_dir_all(contents.join("shared"))?;
+    std::fs::write(contents.join("shared/config.yaml"), "name: {{ name }}")?;
+    symlink("shared/config.yaml", contents.join("config.yaml"))?;
+    symlink("shared", contents.join("shared-link"))?;
+    symlink("{{ name }}/settings", contents.join("{{ name }}.conf"))?;
+    Ok(())
+}
+
+fn render(
+    symlinks: &str,
+    render_context: impl FnOnce(&Utf8Path) -> RenderContext,
+) -> anyhow::Result<(tempfile::TempDir, Utf8PathBuf)> {
+    render_with_link(symlinks, None, render_context)
+}
+
+/// Renders the archetype written by [write_archetype], with an additional link at `contents/escape` to `target`.
+fn render_with_link(
+    symlinks: &str,
+    target: Option<&str>,
+    render_context: impl FnOnce(&Utf8Path) -> RenderContext,
+) -> anyhow::Result<(tempfile::TempDir, Utf8PathBuf)> {
+    let (_archetype_dir, root) = temp_destination()?;
+    write_archetype(&root, symlinks)?;
+    if let Some(target) = target {
+        symlink(target, root.join("contents/escape"))?;
+    }
+
+    let (driver, _handle) = api_driver_and_handle();
+    let archetype = load_archetype(root.as_str(), driver, Configuration::default())?;
+
+    let (temp, destination) = temp_destination()?;
+    let _ = archetype.render(render_context(&destination))?;
+    Ok((temp, destination))
+}
+
+#[test]
+fn test_symlinks_preserved() -> anyhow::Result<()> {
+    // Transactional, so that links are also moved from staging into the existing destination
+    let (_temp, destination) = render("preserve", |destination| {
+        RenderContext::new(destination, Default::default()).with_transactional(true)
+    })?;
+
+    assert_eq!(destination.join("config.yaml").read_link_utf8()?, "shared/config.yaml");
+    assert_eq!(
+        std::fs::read_to_string(destination.join("config.yaml"))?,
+        "name: example"
+    );
+    assert_eq!(destination.join("shared-link").read_link_utf8()?, "shared");
+    assert_eq!(
+        destination.join("example.conf").read_link_utf8()?,
+        "{{ name }}/settings"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_symlink_targets_rendered() -> anyhow::Result<()> {
+    let (_temp, destination) = render("render", |destination| {
+        RenderContext::new(destination, Default::default())
+    })?;
+
+    assert_eq!(destination.join("example.conf").read_link_utf8()?, "example/settings");
+    assert_eq!(destination.join("config.yaml").read_link_utf8()?, "shared/config.yaml");
+
+    Ok(())
+}
+
+#[test]
+fn test_symlinks_dereferenced() -> anyhow::Result<()> {
+    let (_temp, destination) = render("dereference", |destination| {
+        RenderContext::new(destination, Default::default())
+    })?;
+
+    assert!(!destination.join("config.yaml").is_symlink());
+    assert_eq!(
+        std::fs::read_to_string(destination.join("config.yaml"))?,
+        "name: example"
+    );
+    assert!(!destination.join("shared-link").is_symlink());
+    assert_eq!(
+        std::fs::read_to_string(destination.join("shared-link/config.yaml"))?,
+        "name: example"
+    );
+    // Links to missing targets have nothing to render
+    assert!(!destination.join("example.conf").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_symlink_targets_restricted() -> anyhow::Result<()> {
+    for (symlinks, target) in [
+        ("preserve", "/etc/passwd"),
+        ("preserve", "../outside"),
+        ("render", "shared/../../{{ name }}"),
+        ("render", "{{ '/' ~ name }}"),
+    ] {
+        let result = render_with_link(symlinks, Some(target), |destination| {
+            RenderContext::new(destination, Default::default())
+        });
+        assert!(result.is_err(), "{} link to {} was not rejected", symlinks, target);
+    }
+
+    let (_temp, destination) = render_with_link("render", Some("shared/../{{ name }}.conf"), |destination| {
+        RenderContext::new(destination, Default::default())
+    })?;
+    assert_eq!(destination.join("escape").read_link_utf8()?, "shared/../example.conf");
+
+    Ok(())
+}
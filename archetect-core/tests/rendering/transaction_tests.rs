@@ -0,0 +1,60 @@
+use archetect_core::archetype::render_context::RenderContext;
+use archetect_core::errors::ArchetypeError;
+
+use crate::rendering::Fixture;
+
+#[test]
+fn test_transaction_commits_on_success() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination().join("project");
+    let render_context = RenderContext::new(&destination, Default::default());
+    assert!(render_context.transactional());
+
+    let _ = fixture.render_with(render_context)?;
+
+    assert!(destination.join("example.txt").is_file());
+    assert!(destination.join("src").join("main.rs").is_file());
+
+    Ok(())
+}
+
+#[test]
+fn test_transaction_discards_on_failure() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+    std::fs::write(destination.join("existing.txt"), "Existing")?;
+
+    let render_context = fixture
+        .context(Default::default())
+        .with_transactional(true)
+        .with_switch("fail");
+
+    let result = fixture.render_with(render_context);
+    assert!(matches!(result, Err(ArchetypeError::ScriptAbortError)));
+
+    assert!(!destination.join("example.txt").exists());
+    assert!(!destination.join("src").exists());
+    assert_eq!(std::fs::read_to_string(destination.join("existing.txt"))?, "Existing");
+    assert_eq!(std::fs::read_dir(destination)?.count(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_transaction_opt_out() -> anyhow::Result<()> {
+    let fixture = Fixture::new(file!())?;
+    let destination = fixture.destination();
+
+    let render_context = fixture
+        .context(Default::default())
+        .with_transactional(false)
+        .with_switch("fail");
+
+    let result = fixture.render_with(render_context);
+    assert!(matches!(result, Err(ArchetypeError::ScriptAbortError)));
+
+    // Without a transaction, whatever was rendered before the failure is left in place
+    assert!(destination.join("example.txt").is_file());
+
+    Ok(())
+}
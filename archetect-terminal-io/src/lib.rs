@@ -2,6 +2,7 @@ mod bool_prompt_handler;
 mod int_prompt_handler;
 mod list_prompt_handler;
 mod multiselect_prompt_handler;
+mod overwrite_prompt_handler;
 mod select_prompt_handler;
 mod terminal_io_driver;
 mod text_prompt_handler;
@@ -0,0 +1,57 @@
+use std::sync::mpsc::SyncSender;
+
+use diffy::{DiffOptions, PatchFormatter};
+
+use archetect_api::{CommandResponse, OverwriteDecision, OverwritePromptInfo};
+use archetect_inquire::{InquireError, Select};
+
+use crate::get_render_config;
+
+const YES: &str = "Yes";
+const NO: &str = "No";
+const ALL: &str = "All (overwrite this and all remaining files)";
+const NONE: &str = "None (preserve this and all remaining files)";
+const DIFF: &str = "Show diff";
+
+pub fn handle_overwrite_prompt(prompt_info: OverwritePromptInfo, responses: &SyncSender<CommandResponse>) {
+    let message = prompt_info.message();
+    loop {
+        let prompt = Select::new(&message, vec![YES, NO, ALL, NONE, DIFF])
+            .with_render_config(get_render_config());
+
+        let decision = match prompt.prompt_skippable() {
+            Ok(Some(YES)) => OverwriteDecision::Yes,
+            Ok(Some(ALL)) => OverwriteDecision::All,
+            Ok(Some(NONE)) => OverwriteDecision::None,
+            Ok(Some(DIFF)) => {
+                eprintln!("{}", unified_diff(&prompt_info));
+                continue;
+            }
+            Ok(_) => OverwriteDecision::No,
+            Err(InquireError::OperationCanceled | InquireError::OperationInterrupted) => {
+                responses.send(CommandResponse::Abort).expect("Channel Send Error");
+                return;
+            }
+            Err(error) => {
+                responses
+                    .send(CommandResponse::Error(error.to_string()))
+                    .expect("Channel Send Error");
+                return;
+            }
+        };
+        responses
+            .send(CommandResponse::Overwrite(decision))
+            .expect("Channel Send Error");
+        return;
+    }
+}
+
+fn unified_diff(prompt_info: &OverwritePromptInfo) -> String {
+    let patch = DiffOptions::new()
+        .set_original_filename(format!("{} (existing)", prompt_info.destination()))
+        .set_modified_filename(format!("{} (rendered)", prompt_info.destination()))
+        .create_patch(prompt_info.existing(), prompt_info.proposed());
+    let formatter = PatchFormatter::new().with_color();
+    let diff = formatter.fmt_patch(&patch).to_string();
+    diff
+}
@@ -10,6 +10,7 @@ use crate::bool_prompt_handler::handle_prompt_bool;
 use crate::editor_prompt_info::handle_editor_prompt;
 use crate::int_prompt_handler::handle_prompt_int;
 use crate::multiselect_prompt_handler::handle_multiselect_prompt;
+use crate::overwrite_prompt_handler::handle_overwrite_prompt;
 use crate::select_prompt_handler::handle_select_prompt;
 use crate::text_prompt_handler::handle_prompt_text;
 
@@ -43,6 +44,9 @@ impl IoDriver for TerminalIoDriver {
             CommandRequest::PromptForEditor(prompt_info) => {
                 handle_editor_prompt(prompt_info, &self.responses_tx);
             }
+            CommandRequest::PromptForOverwrite(prompt_info) => {
+                handle_overwrite_prompt(prompt_info, &self.responses_tx);
+            }
             CommandRequest::LogInfo(message) => {
                 info!("{}", message)
             }
@@ -64,12 +68,31 @@ impl IoDriver for TerminalIoDriver {
             CommandRequest::Display(message) => {
                 eprintln!("{}", message)
             }
+            CommandRequest::DirectoryCreated(path) => {
+                debug!("Created     {}", path)
+            }
+            CommandRequest::FileRendered(event) => {
+                info!("Rendered    {}", event.destination())
+            }
+            CommandRequest::FileCopied(event) => {
+                info!("Copied      {}", event.destination())
+            }
+            CommandRequest::FilePreserved(event) => {
+                info!("Preserved   {}", event.destination())
+            }
+            CommandRequest::OverwriteSkipped(event) => {
+                info!("Skipped     {}", event.destination())
+            }
         }
     }
 
     fn responses(&self) -> Arc<Mutex<Receiver<CommandResponse>>> {
         self.responses_rx.clone()
     }
+
+    fn render_events(&self) -> bool {
+        true
+    }
 }
 
 impl Default for TerminalIoDriver {